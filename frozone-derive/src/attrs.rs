@@ -0,0 +1,300 @@
+//! `#[frozone(...)]` and `#[repr(...)]` attributes
+use quote::{quote, quote_spanned};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Error, Result, Token};
+
+/// `#[frozone(...)]` and `#[repr(...)]` attributes of a struct or enum
+#[derive(Default)]
+pub struct Container {
+    /// `positional`: the order of the fields or variants is frozen (by v2)
+    pub positional: bool,
+    /// the `#[repr(...)]` items, e.g `C, align(8)`
+    pub repr: Option<String>,
+    /// `remote = "path"`: the type mirrors that (foreign) type
+    pub remote: Option<syn::Path>,
+}
+
+impl Container {
+    pub fn parse(attrs: &[syn::Attribute]) -> Result<Self> {
+        let mut container = Container::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("frozone")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("positional") {
+                    container.positional = true;
+                    Ok(())
+                } else if meta.path.is_ident("remote") {
+                    let remote: syn::LitStr = meta.value()?.parse()?;
+                    container.remote = Some(remote.parse()?);
+                    Ok(())
+                } else {
+                    Err(Error::new_spanned(meta.path, "unknown frozone attribute"))
+                }
+            })?;
+        }
+        let mut repr = Vec::new();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("repr")) {
+            let items =
+                attr.parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)?;
+            // `align (8)` -> `align(8)`
+            repr.extend(items.iter().map(|item| {
+                let item = quote!(#item).to_string();
+                item.split_whitespace().collect::<String>()
+            }));
+        }
+        if !repr.is_empty() {
+            container.repr = Some(repr.join(", "));
+        }
+        Ok(container)
+    }
+
+    /// name of the type in its schema: the one of the remote type, if any
+    pub fn name(&self, ident: &syn::Ident) -> String {
+        match self.remote.as_ref().and_then(|r| r.segments.last()) {
+            Some(segment) => segment.ident.to_string(),
+            None => ident.to_string(),
+        }
+    }
+
+    /// `Option<Layout>` expression of a `#[repr(...)]` type, with the
+    /// offsets of `fields` (none for enums)
+    ///
+    /// Structs with an unsized last field (e.g `data: [u8]`) have no layout,
+    /// their size not being known at compile-time
+    pub fn layout(
+        &self,
+        generics: &syn::Generics,
+        fields: Option<&syn::Fields>,
+    ) -> proc_macro2::TokenStream {
+        let Some(repr) = &self.repr else {
+            return quote! { None };
+        };
+        let last = fields.and_then(|fields| fields.iter().last());
+        if last.is_some_and(|f| maybe_unsized(&f.ty, generics)) {
+            return quote! { None };
+        }
+        // the layout of a mirror is the one of the remote type
+        let ty = match &self.remote {
+            Some(remote) => quote! { #remote },
+            None => quote! { Self },
+        };
+        let members = fields.into_iter().flat_map(|fields| fields.members());
+        quote! {
+            Some(frozone::schema::Layout {
+                repr: #repr.into(),
+                size: ::core::mem::size_of::<#ty>(),
+                align: ::core::mem::align_of::<#ty>(),
+                offsets: [
+                    #(::core::mem::offset_of!(#ty, #members),)*
+                ].into(),
+            })
+        }
+    }
+
+    /// never called function, that fails to compile if the fields of the
+    /// mirror struct aren't the ones of the remote type (with the same types)
+    pub fn remote_struct_check(
+        &self,
+        generics: &syn::Generics,
+        fields: &syn::Fields,
+    ) -> proc_macro2::TokenStream {
+        let Some(path) = self.remote_pattern_path() else {
+            return quote! {};
+        };
+        let (pattern, checks) = remote_pattern(quote! { #path }, fields);
+        self.remote_check(generics, quote! { let #pattern = remote; #checks })
+    }
+
+    /// never called function, that fails to compile if the variants of the
+    /// mirror enum aren't the ones of the remote type (with the same fields)
+    pub fn remote_enum_check(
+        &self,
+        generics: &syn::Generics,
+        data: &syn::DataEnum,
+    ) -> proc_macro2::TokenStream {
+        let Some(path) = self.remote_pattern_path() else {
+            return quote! {};
+        };
+        let arms = data.variants.iter().map(|v| {
+            let name = &v.ident;
+            let (pattern, checks) = remote_pattern(quote! { #path::#name }, &v.fields);
+            quote! { #pattern => { #checks } }
+        });
+        self.remote_check(generics, quote! { match remote { #(#arms)* } })
+    }
+
+    /// the remote type, in patterns: `other::Wrapper<T>` -> `other::Wrapper`
+    fn remote_pattern_path(&self) -> Option<syn::Path> {
+        let mut path = self.remote.clone()?;
+        if let Some(segment) = path.segments.last_mut() {
+            segment.arguments = syn::PathArguments::None;
+        }
+        Some(path)
+    }
+
+    fn remote_check(
+        &self,
+        generics: &syn::Generics,
+        body: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let Some(remote) = &self.remote else {
+            return quote! {};
+        };
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        quote! {
+            const _: () = {
+                #[allow(dead_code, unused_variables)]
+                fn __frozone_remote_check #impl_generics (remote: &#remote) #where_clause {
+                    #body
+                }
+            };
+        }
+    }
+}
+
+/// exhaustive pattern (without `..`) of a remote struct or variant with
+/// the mirror's `fields`, along with the checks of the types of its fields
+fn remote_pattern(
+    path: proc_macro2::TokenStream,
+    fields: &syn::Fields,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let bindings: Vec<_> = (0..fields.len())
+        .map(|i| quote::format_ident!("__field{}", i))
+        .collect();
+    let checks = fields.iter().zip(&bindings).map(|(f, binding)| {
+        let ty = checked_type(f);
+        quote_spanned! { f.ty.span()=> let _: &#ty = #binding; }
+    });
+    let pattern = match fields {
+        syn::Fields::Unit => quote! { #path },
+        syn::Fields::Unnamed(_) => quote! { #path(#(#bindings),*) },
+        syn::Fields::Named(_) => {
+            let members = fields.members();
+            quote! { #path { #(#members: #bindings),* } }
+        }
+    };
+    (pattern, quote! { #(#checks)* })
+}
+
+/// whether the type may be unsized: slices, `str`, trait objects and the
+/// `?Sized` generic parameters (as far as can be told from its tokens)
+fn maybe_unsized(ty: &syn::Type, generics: &syn::Generics) -> bool {
+    let unsized_param = |ident: &syn::Ident| {
+        let maybe = |bounds: &Punctuated<syn::TypeParamBound, Token![+]>| {
+            bounds.iter().any(|bound| {
+                matches!(bound, syn::TypeParamBound::Trait(t)
+                    if matches!(t.modifier, syn::TraitBoundModifier::Maybe(_)))
+            })
+        };
+        let in_params = generics
+            .type_params()
+            .any(|p| p.ident == *ident && maybe(&p.bounds));
+        let in_where = generics.where_clause.iter().any(|w| {
+            w.predicates.iter().any(|predicate| match predicate {
+                syn::WherePredicate::Type(t) => {
+                    matches!(&t.bounded_ty, syn::Type::Path(p) if p.path.is_ident(ident))
+                        && maybe(&t.bounds)
+                }
+                _ => false,
+            })
+        });
+        in_params || in_where
+    };
+    match ty {
+        syn::Type::Slice(_) | syn::Type::TraitObject(_) => true,
+        syn::Type::Paren(p) => maybe_unsized(&p.elem, generics),
+        syn::Type::Group(g) => maybe_unsized(&g.elem, generics),
+        syn::Type::Path(p) if p.qself.is_none() => {
+            let dst = |s: &syn::PathSegment| ["str", "CStr", "OsStr"].iter().any(|d| s.ident == d);
+            p.path.segments.last().is_some_and(dst) || p.path.get_ident().is_some_and(unsized_param)
+        }
+        _ => false,
+    }
+}
+
+/// type of the remote field that a mirror field is checked against: any
+/// for the fields that aren't frozen as their type
+fn checked_type(f: &syn::Field) -> proc_macro2::TokenStream {
+    match Field::parse(&f.attrs) {
+        Ok(Field {
+            with: None,
+            proxy: None,
+        }) => {
+            let ty = &f.ty;
+            quote! { #ty }
+        }
+        _ => quote! { _ },
+    }
+}
+
+/// `#[frozone(...)]` attributes of a field
+#[derive(Default)]
+pub struct Field {
+    /// `with = path`: function building the schema of the field
+    /// (with the signature of `Freezable::schema_with_context`)
+    pub with: Option<syn::ExprPath>,
+    /// `as = Type`: the field is frozen as if it were of that (proxy) type
+    pub proxy: Option<syn::Type>,
+}
+
+impl Field {
+    pub fn parse(attrs: &[syn::Attribute]) -> Result<Self> {
+        let mut field = Field::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("frozone")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("with") {
+                    field.with = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("as") {
+                    field.proxy = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(Error::new_spanned(meta.path, "unknown frozone attribute"))
+                }
+            })?;
+        }
+        if let (Some(with), Some(_)) = (&field.with, &field.proxy) {
+            return Err(Error::new_spanned(
+                with,
+                "#[frozone(with)] and #[frozone(as)] can't be used together",
+            ));
+        }
+        if let Some(attr) = attrs.iter().find(|a| a.path().is_ident("assume_frozen")) {
+            field.assumed(attr)?;
+        }
+        Ok(field)
+    }
+
+    /// error if the field, assumed frozen by the `#[assume_frozen]`
+    /// attribute `assume` (its own or its variant's), has a schema
+    pub fn assumed(&self, assume: &syn::Attribute) -> Result<()> {
+        let attribute = match (&self.with, &self.proxy) {
+            (None, None) => return Ok(()),
+            (Some(_), _) => "#[frozone(with)]",
+            (None, Some(_)) => "#[frozone(as)]",
+        };
+        Err(Error::new_spanned(
+            assume,
+            format!("{} can't be used on #[assume_frozen] fields", attribute),
+        ))
+    }
+
+    /// schema of the field, along with its compile-time freeze, if it
+    /// isn't the one of its type
+    pub fn custom(
+        &self,
+        lazy: &mut crate::Lazy,
+    ) -> Option<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+        if let Some(proxy) = &self.proxy {
+            return Some((
+                quote! { <#proxy as frozone::Freezable>::schema_with_context(ctx) },
+                lazy.freeze(proxy),
+            ));
+        }
+        let with = self.with.as_ref()?;
+        Some((
+            quote! { #with(ctx) },
+            quote! { frozone::internals::no_const_freeze() },
+        ))
+    }
+}
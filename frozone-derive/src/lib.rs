@@ -1,24 +1,35 @@
 extern crate proc_macro2;
+mod attrs;
+mod serde;
+
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
 
-use syn::{Error, GenericParam, Generics, Result};
+use syn::{Error, Generics, Result};
 
-#[proc_macro_derive(Freezable, attributes(assume_frozen))]
+// `serde` is also declared so that the serde attributes can be used without serde's derive
+#[proc_macro_derive(Freezable, attributes(assume_frozen, frozone, serde))]
 pub fn derive_freezable(input: TokenStream) -> TokenStream {
     let ast: syn::DeriveInput = syn::parse_macro_input!(input);
     let name = &ast.ident;
     let generics = ast.generics;
 
-    let res = match ast.data {
-        syn::Data::Struct(data) => derive_freezable_struct(data, name, &generics),
-        syn::Data::Enum(data) => derive_freezable_enum(data, name, &generics),
-        _ => Err(Error::new(
-            Span::call_site(),
-            "can only derive trait Freezable for struct or enums",
-        )),
-    };
+    let res = attrs::Container::parse(&ast.attrs).and_then(|attrs| {
+        let container = serde::Container::parse(&ast.attrs)?;
+        match ast.data {
+            syn::Data::Struct(data) => {
+                derive_freezable_struct(data, name, &generics, &attrs, &container)
+            }
+            syn::Data::Enum(data) => {
+                derive_freezable_enum(data, name, &generics, &attrs, &container)
+            }
+            _ => Err(Error::new(
+                Span::call_site(),
+                "can only derive trait Freezable for struct or enums",
+            )),
+        }
+    });
     match res {
         Ok(s) => s,
         Err(e) => e.to_compile_error().into(),
@@ -26,45 +37,60 @@ pub fn derive_freezable(input: TokenStream) -> TokenStream {
 }
 
 /// generate Freezable impl for the enum
-/// (that recursively call `freeze_with_context(ctx)` on all non-excluded
+/// (that recursively call `schema_with_context(ctx)` on all non-excluded
 /// variant and their fields' types)
 fn derive_freezable_enum(
     data: syn::DataEnum,
     name: &syn::Ident,
     generics: &Generics,
+    attrs: &attrs::Container,
+    container: &serde::Container,
 ) -> Result<TokenStream> {
-    let variants_names_and_freezes = data.variants.iter().map(|f| {
+    let mut lazy = Lazy::new(name, generics);
+    let mut variants = Vec::new();
+    let mut variant_freezes = Vec::new();
+    for f in data.variants.iter() {
         let name = &f.ident;
+        let shape = shape(&f.fields);
+        let variant_serde = serde::Variant::parse(&f.attrs, name, container)?;
+        let serde = variant_serde.to_tokens();
         if let Some(af) = f.attrs.iter().find(|a| a.path().is_ident("assume_frozen")) {
-            if attr_helper_freeze_generics(af) {
-                // the variant's field types still freezes their generic arguments
-                // (but not themselves)
-                let variant_fields = f
+            for g in f.fields.iter() {
+                attrs::Field::parse(&g.attrs)?.assumed(af)?;
+            }
+            // the variant's field types still freezes their generic arguments
+            // (but not themselves), or are completely ignored
+            let (variant_fields, field_freezes) = if attr_helper_freeze_generics(af) {
+                let (fields, freezes): (Vec<_>, Vec<_>) = f
                     .fields
                     .iter()
-                    .map(|g| freeze_field_only_generics(&g.ty, name));
-
-                quote! {{
-                    let x: NF = Box::new(|ctx|
-                        (stringify!(#name), {
-                            let mut hasher = core::hash::SipHasher::new();
-
-                            [#(#variant_fields,)*].iter().for_each(|x: &NF| {
-                                x(ctx).1.hash(&mut hasher);
-                            });
-                            hasher.finish()
-                        }));
-                    x
-                }}
+                    .enumerate()
+                    .map(|(i, g)| {
+                        let (schema, freeze) = freeze_field_only_generics(&g.ty, &mut lazy)?;
+                        let schema = at(&format!("::{}", name), g, i, schema);
+                        let serde = serde::Field::parse(g, variant_serde.fields())?;
+                        Ok((field(g, i, schema, serde), freeze))
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .into_iter()
+                    .unzip();
+                (quote! { [#(#fields,)*].into() }, freezes)
             } else {
-                // completely ignore the variant
-                quote! {{
-                    let x :NF = Box::new(|ctx|
-                        (stringify!(#name), 0)
-                    );
-                    x
-                }}
-            }
+                (quote! { [].into() }, Vec::new())
+            };
+            variants.push(quote! {
+                frozone::schema::Variant {
+                    name: stringify!(#name).into(),
+                    discriminant: None,
+                    shape: #shape,
+                    fields: #variant_fields,
+                    assume_frozen: true,
+                    serde: #serde,
+                }
+            });
+            variant_freezes.push(quote! {
+                (stringify!(#name), frozone::hash::v1::variant(None, true, &[#(#field_freezes,)*]))
+            });
         } else {
             // handle simple cases such as `enum M {A = 1}`
             let discriminant = f
@@ -72,279 +98,508 @@ fn derive_freezable_enum(
                 .as_ref()
                 .map(|eq_d| eq_d.1.clone())
                 .map(|d| {
-                    quote! {
-                    use core::hash::Hasher;
-                    let mut hasher = core::hash::SipHasher::new();
-                        (#d).hash(&mut hasher);
-                    hasher.finish()
-                    }
+                    quote! {{
+                        let d = #d;
+                        Some(frozone::schema::Discriminant {
+                            value: d as i128,
+                            size: frozone::internals::discriminant_size(&d),
+                        })
+                    }}
                 })
-                .unwrap_or(quote! {0});
+                .unwrap_or(quote! {None});
 
             // freeze all fields of a variant `enum M { A(u8, OtherType, etc...) }`
-            let variant_fields = f.fields.iter().map(|g| {
-                let g_ty = &g.ty;
-                quote! {{
-                    let x: F = Box::new(|ctx|
-                        <#g_ty as Freezable>::freeze_with_context(ctx)
-                    );
-                    x
-                }}
-            });
+            let variant_fields = f
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, g)| {
+                    let g_ty = &g.ty;
+                    let serde = serde::Field::parse(g, variant_serde.fields())?;
+                    let field_attrs = attrs::Field::parse(&g.attrs)?;
+                    let (schema, freeze) = if serde.skipped() {
+                        // the type of a `#[serde(skip)]` field isn't frozen
+                        (
+                            quote! { frozone::Schema::AssumeFrozen },
+                            quote! { frozone::hash::v1::ASSUME_FROZEN },
+                        )
+                    } else if let Some((schema, freeze)) = field_attrs.custom(&mut lazy) {
+                        (at(&format!("::{}", name), g, i, schema), freeze)
+                    } else {
+                        let schema =
+                            quote! { <#g_ty as frozone::Freezable>::schema_with_context(ctx) };
+                        (at(&format!("::{}", name), g, i, schema), lazy.freeze(g_ty))
+                    };
+                    Ok((field(g, i, schema, serde), freeze))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let (variant_fields, field_freezes): (Vec<_>, Vec<_>) =
+                variant_fields.into_iter().unzip();
 
-            #[cfg(not(feature = "std"))]
-            let display_variant = quote! {};
-            #[cfg(feature = "std")]
-            let display_variant = quote! {{
-                if ctx.display {
-                    println!("{:\t<4$}({}.{}): {:#018x}", "", stringify!(#name),i ,a ,ctx.depth as usize);
+            variants.push(quote! {
+                frozone::schema::Variant {
+                    name: stringify!(#name).into(),
+                    discriminant: #discriminant,
+                    shape: #shape,
+                    fields: [#(#variant_fields,)*].into(),
+                    assume_frozen: false,
+                    serde: #serde,
                 }
-            }};
-
-            // combine all into the enum's final freeze
-            quote! { {
-                let x: NF = Box::new(|ctx|
-                    (stringify!(#name), {
-                        let mut hasher = core::hash::SipHasher::new();
-
-                        #discriminant.hash(&mut hasher);
-                        [#(#variant_fields,)*].iter().enumerate().for_each(|(i,x): (usize,&F)| {
-                            {
-                                let a = x(ctx);
-                                #display_variant
-                                a.hash(&mut hasher);
-                            }
-                        });
-                        hasher.finish()
-                    })
-                );
-                x
-            }}
+            });
+            variant_freezes.push(quote! {
+                (
+                    stringify!(#name),
+                    frozone::hash::v1::variant(#discriminant, false, &[#(#field_freezes,)*]),
+                )
+            });
         }
-    });
+    }
 
-    #[cfg(not(feature = "std"))]
-    let display_enum = quote! {};
-    #[cfg(feature = "std")]
-    let display_enum = quote! {{
-        if ctx.display {
-            println!("{:\t<2$}({})", "", stringify!(#name),ctx.depth as usize - 1);
+    let representation = container.to_tokens();
+    let positional = attrs.positional;
+    let layout = attrs.layout(generics, None);
+    let type_name = attrs.name(name);
+    let schema = quote! {
+        frozone::Schema::Enum {
+            name: #type_name.into(),
+            variants: [#(#variants,)*].into(),
+            serde: #representation,
+            positional: #positional,
+            layout: #layout,
         }
-    }};
-
-    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
-    let unit_generics = generics_to_unit(generics);
-    Ok(quote! {
-        use frozone::internals::*;
-        impl #impl_generics Freezable for #name #type_generics #where_clause {
-            fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-                let t_id = core::any::TypeId::of::<#name #unit_generics>();
-                if let Some((_t, first_depth)) = ctx.cache.iter().find(|(t,_d)| *t == t_id) {
-                    // loop detected ! stop recursion and return something 'unique'.
-                    // The 'depth' between the first occurence and now is a 'good' candidate,
-                    // since replacing this type by another equivalent one (not changing semantics,
-                    // per se, but as the global 'structure' graph gets modified..)
-                    return *first_depth as u64 + 1;
-                }
-                ctx.depth += 1;
-                ctx.cache.push((t_id, ctx.depth));
-                #display_enum
-                let freeze = [#(#variants_names_and_freezes,)*].iter().fold(0u64, |acc, x: &NF|
-                    nf_freeze(x, ctx, acc)
-                );
-                ctx.cache.pop();
-                ctx.depth -= 1;
-                freeze
-            }
-        }
-    }
-    .into())
+    };
+    let freeze = quote! { frozone::hash::v1::sum_named(&[#(#variant_freezes,)*]) };
+    let check = attrs.remote_enum_check(generics, &data);
+    let derived = derive_impl(name, generics, attrs, schema, freeze, lazy);
+    Ok(quote! { #derived #check }.into())
 }
 
 /// generate Freezable impl for the struct (that recursively
-/// call `freeze_with_context(ctx)` on all non-excluded fields' types
+/// call `schema_with_context(ctx)` on all non-excluded fields' types
 fn derive_freezable_struct(
     data: syn::DataStruct,
     name: &syn::Ident,
     generics: &Generics,
+    attrs: &attrs::Container,
+    container: &serde::Container,
 ) -> Result<TokenStream> {
-    let fields = data.fields.iter().map(|f| {
-        // for tuple struct definitions e.g. `struct MyInstant(std::time::Instant)`
-        let empty_ident = proc_macro2::Ident::new("_", proc_macro2::Span::call_site());
-        let name = &f.ident.as_ref().unwrap_or(&empty_ident);
+    let mut lazy = Lazy::new(name, generics);
+    let mut fields = Vec::new();
+    let mut field_freezes = Vec::new();
+    for (i, f) in data.fields.iter().enumerate() {
         let ty = &f.ty;
-        if let Some(af) = f.attrs.iter().find(|a| a.path().is_ident("assume_frozen")) {
-            if attr_helper_freeze_generics(af) {
-                // field type still freezes the generic arguments of its type
-                // (but not the type itself)
-                freeze_field_only_generics(ty, name)
+        let serde = serde::Field::parse(f, container.fields())?;
+        let field_attrs = attrs::Field::parse(&f.attrs)?;
+        let (schema, freeze) =
+            if let Some(af) = f.attrs.iter().find(|a| a.path().is_ident("assume_frozen")) {
+                if attr_helper_freeze_generics(af) {
+                    // field type still freezes the generic arguments of its type
+                    // (but not the type itself)
+                    let (schema, freeze) = freeze_field_only_generics(ty, &mut lazy)?;
+                    (at("", f, i, schema), freeze)
+                } else {
+                    (
+                        quote! { frozone::Schema::AssumeFrozen },
+                        quote! { frozone::hash::v1::ASSUME_FROZEN },
+                    )
+                }
+            } else if serde.skipped() {
+                // the type of a `#[serde(skip)]` field isn't frozen
+                (
+                    quote! { frozone::Schema::AssumeFrozen },
+                    quote! { frozone::hash::v1::ASSUME_FROZEN },
+                )
+            } else if let Some((schema, freeze)) = field_attrs.custom(&mut lazy) {
+                (at("", f, i, schema), freeze)
             } else {
-                quote! {{
-                    let x: NF = Box::new(|ctx|
-                        (stringify!(#name), 0)
-                    );
-                    x
-                }}
-            }
-        } else {
-            quote! {{
-                let x: NF = Box::new(|ctx| (
-                    stringify!(#name),
-                    <#ty as Freezable>::freeze_with_context(ctx)
-                ));
-                x
-            }}
-        }
-    });
+                let schema = quote! { <#ty as frozone::Freezable>::schema_with_context(ctx) };
+                (at("", f, i, schema), lazy.freeze(ty))
+            };
+        // unnamed fields are all hashed as `_`
+        let field_name = match &f.ident {
+            Some(ident) => ident.to_string(),
+            None => "_".to_string(),
+        };
+        fields.push(field(f, i, schema, serde));
+        field_freezes.push(quote! { (#field_name, #freeze) });
+    }
 
-    #[cfg(not(feature = "std"))]
-    let display_struct = quote! {};
-    #[cfg(feature = "std")]
-    let display_struct = quote! {{
-        if ctx.display {
-            println!("{:\t<2$}({})", "", stringify!(#name),ctx.depth as usize - 1);
+    let shape = shape(&data.fields);
+    let representation = container.to_tokens();
+    let positional = attrs.positional;
+    let layout = attrs.layout(generics, Some(&data.fields));
+    let type_name = attrs.name(name);
+    let schema = quote! {
+        frozone::Schema::Struct {
+            name: #type_name.into(),
+            shape: #shape,
+            fields: [#(#fields,)*].into(),
+            serde: #representation,
+            positional: #positional,
+            layout: #layout,
         }
-    }};
+    };
+    let freeze = quote! { frozone::hash::v1::sum_named(&[#(#field_freezes,)*]) };
+    let check = attrs.remote_struct_check(generics, &data.fields);
+    let derived = derive_impl(name, generics, attrs, schema, freeze, lazy);
+    Ok(quote! { #derived #check }.into())
+}
 
+/// wraps the schema-building expression of a type in its Freezable impl
+/// (that takes care of the type-recursiveness), and the compile-time
+/// freeze expression in an `internals::ConstFreeze` impl (that its
+/// `FREEZE_V1` is read from)
+fn derive_impl(
+    name: &syn::Ident,
+    generics: &Generics,
+    attrs: &attrs::Container,
+    schema: proc_macro2::TokenStream,
+    freeze: proc_macro2::TokenStream,
+    lazy: Lazy,
+) -> proc_macro2::TokenStream {
     let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
-    let unit_generics = generics_to_unit(generics);
-    let generated = quote! {
-        use frozone::internals::*;
-        impl #impl_generics Freezable for #name #type_generics #where_clause {
-            fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
+    let type_name = attrs.name(name);
+
+    // the const freeze is computed by a type generic over `__M`
+    // (that the field types freeze are read through), to only
+    // be evaluated when used
+    let mut lazy_generics = generics.clone();
+    lazy_generics.params.push(syn::parse_quote! { __M: ?Sized });
+    let lazy_where = lazy_generics.make_where_clause();
+    for ty in &lazy.types {
+        lazy_where
+            .predicates
+            .push(syn::parse_quote! { __M: frozone::internals::LazyFreeze<#ty> });
+    }
+    let (lazy_impl_generics, _, lazy_where_clause) = lazy_generics.split_for_impl();
+
+    quote! {
+        const _: () = {
+            // identifies the derived type (along with its generic arguments)
+            pub struct __FrozoneDerived;
 
-                let t_id = core::any::TypeId::of::<#name #unit_generics>();
-                // all possible lifetimes to 'static
-                if let Some((_t, first_depth)) = ctx.cache.iter().find(|(t,_d)| *t == t_id) {
-                    // loop detected ! stop recursion and return something 'unique'.
-                    // The 'depth' between the first occurence and now is a 'good' candidate,
-                    // since replacing this type by another equivalent one (not changing semantics,
-                    // per se, but as the global 'structure' graph gets modified..)
-                    return *first_depth as u64 + 1;
+            pub struct __FrozoneLazy<M: ?Sized, S: ?Sized>(
+                ::core::marker::PhantomData<M>,
+                ::core::marker::PhantomData<S>,
+            );
+
+            impl #lazy_impl_generics frozone::internals::ConstFreeze
+                for __FrozoneLazy<__M, #name #type_generics> #lazy_where_clause
+            {
+                const FREEZE_V1: u64 = #freeze;
+            }
+
+            impl #impl_generics frozone::Freezable for #name #type_generics #where_clause {
+                const FREEZE_V1: u64 = frozone::internals::derived_freeze_v1::<
+                    __FrozoneLazy<frozone::internals::ConstEval, Self>,
+                >();
+
+                fn schema_with_context(ctx: &mut frozone::FreezeCtx) -> frozone::Schema {
+                    let key = frozone::TypeKey::of::<__FrozoneDerived, Self>();
+                    if let Some(schema) = ctx.enter(key, #type_name) {
+                        // back-edge to the first occurence (or failed freezing)
+                        return schema;
+                    }
+                    let schema = #schema;
+                    ctx.leave();
+                    schema
                 }
-                ctx.depth += 1;
-                ctx.cache.push((t_id, ctx.depth));
+            }
+        };
+    }
+}
 
-                #display_struct
-                let freeze = [#(#fields,)*].iter().fold(0u64, |acc: u64, x: &NF|
-                    nf_freeze(x, ctx, acc)
-                );
+/// field types whose compile-time freeze is used by the derived type
+struct Lazy {
+    /// `Self` in the field types, that don't appear in the `Freezable` impl
+    self_ty: proc_macro2::TokenStream,
+    types: Vec<proc_macro2::TokenStream>,
+}
+
+impl Lazy {
+    fn new(name: &syn::Ident, generics: &Generics) -> Self {
+        let (_, type_generics, _) = generics.split_for_impl();
+        Lazy {
+            self_ty: quote! { #name #type_generics },
+            types: Vec::new(),
+        }
+    }
 
-                ctx.cache.pop();
-                ctx.depth -= 1;
-                freeze
+    /// compile-time freeze of the type, read through `LazyFreeze`
+    fn freeze(&mut self, ty: &syn::Type) -> proc_macro2::TokenStream {
+        let ty = replace_self(quote! { #ty }, &self.self_ty);
+        self.types.push(ty.clone());
+        quote! { <__M as frozone::internals::LazyFreeze<#ty>>::FREEZE_V1 }
+    }
+}
+
+fn replace_self(
+    tokens: proc_macro2::TokenStream,
+    self_ty: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    tokens
+        .into_iter()
+        .map(|tt| match tt {
+            proc_macro2::TokenTree::Ident(ident) if ident == "Self" => self_ty.clone(),
+            proc_macro2::TokenTree::Group(group) => {
+                let mut new_group = proc_macro2::Group::new(
+                    group.delimiter(),
+                    replace_self(group.stream(), self_ty),
+                );
+                new_group.set_span(group.span());
+                proc_macro2::TokenTree::Group(new_group).into()
             }
+            tt => tt.into(),
+        })
+        .collect()
+}
+
+/// `frozone::schema::Field` of the (possibly unnamed) field at position `i`
+fn field(
+    f: &syn::Field,
+    i: usize,
+    schema: proc_macro2::TokenStream,
+    serde: serde::Field,
+) -> proc_macro2::TokenStream {
+    let name = match &f.ident {
+        Some(ident) => ident.to_string(),
+        None => i.to_string(),
+    };
+    let serde = serde.to_tokens();
+    quote! {
+        frozone::schema::Field {
+            name: #name.into(),
+            schema: #schema,
+            serde: #serde,
         }
+    }
+}
+
+/// builds the schema of the field at position `i` under its path segment
+/// (`prefix` being `::Variant` for variant fields), for `FreezeError`s
+fn at(
+    prefix: &str,
+    f: &syn::Field,
+    i: usize,
+    schema: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let segment = match &f.ident {
+        Some(ident) => format!("{}.{}", prefix, ident),
+        None => format!("{}.{}", prefix, i),
     };
+    quote! { ctx.at(#segment, |ctx| #schema) }
+}
 
-    Ok(generated.into())
+fn shape(fields: &syn::Fields) -> proc_macro2::TokenStream {
+    match fields {
+        syn::Fields::Unit => quote! { frozone::schema::Shape::Unit },
+        syn::Fields::Unnamed(_) => quote! { frozone::schema::Shape::Tuple },
+        syn::Fields::Named(_) => quote! { frozone::schema::Shape::Named },
+    }
 }
 
-/// generate a quote! that freezes a type but only over its generic
-/// arguments (they must impl Freezable). The returned arg is a NF function
-fn freeze_field_only_generics(ty: &syn::Type, name: &syn::Ident) -> proc_macro2::TokenStream {
+/// generate a quote! that builds the schema of a type but only over its generic
+/// arguments (they must impl Freezable), along with the matching compile-time freeze
+///
+/// Paths (and trait objects) are a `Schema::FrozenGenerics` of their segments,
+/// the other types (tuples, arrays, references...) are descended into, down to
+/// the paths they contain: `(Vec<A>, [Rc<B>; 2])` only freezes `A` and `B`
+fn freeze_field_only_generics(
+    ty: &syn::Type,
+    lazy: &mut Lazy,
+) -> Result<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    // `name<descended elem>`
+    let container = |name: &str, elem: &syn::Type, lazy: &mut Lazy| {
+        let (schema, freeze) = freeze_field_only_generics(elem, lazy)?;
+        Ok((
+            quote! {
+                frozone::Schema::Container {
+                    name: #name.into(),
+                    args: [#schema].into(),
+                }
+            },
+            quote! { frozone::hash::v1::container(#name, &[#freeze]) },
+        ))
+    };
     match ty {
-        syn::Type::Path(p) => {
-            let type_segments = p.path.segments.iter().map(|ps| {
-                match &ps.arguments {
-                    syn::PathArguments::AngleBracketed(bracketed) => {
-                        let generics = bracketed
-                            .args
-                            .iter()
-                            .filter_map(|g| match g {
-                                syn::GenericArgument::Type(t) => Some(t),
-                                _ => None,
-                            })
-                            .map(|t| {
-                                quote! {{
-                                    let x: F = Box::new(|ctx| (
-                                        <#t as Freezable>::freeze_with_context(ctx)
-                                    ));
-                                    x
-                                }}
-                            });
-                        quote! {{
-                            let x: F = Box::new(|ctx| ({
-                                let mut hasher = core::hash::SipHasher::new();
-                                "GenericType".hash(&mut hasher); // prevent collisions with parenthesized generics
-                                [#(#generics,)*].iter().for_each(|x: &F| {
-                                    x(ctx).hash(&mut hasher);
-                                });
-                                hasher.finish()
-                            }));
-                            x
-                        }}
+        syn::Type::Path(p) => Ok(frozen_generics(&p.path.segments, lazy)),
+        syn::Type::TraitObject(syn::TypeTraitObject { bounds, .. })
+        | syn::Type::ImplTrait(syn::TypeImplTrait { bounds, .. }) => {
+            // the segments of every trait, e.g `Fn(A) -> B` and `Send`
+            let segments: Vec<_> = bounds
+                .iter()
+                .filter_map(|bound| match bound {
+                    syn::TypeParamBound::Trait(t) => Some(&t.path.segments),
+                    _ => None,
+                })
+                .flatten()
+                .cloned()
+                .collect();
+            Ok(frozen_generics(&segments, lazy))
+        }
+        // frozen as a `Fn(A) -> B` segment
+        syn::Type::BareFn(f) => {
+            let segment = syn::PathSegment {
+                ident: quote::format_ident!("fn"),
+                arguments: syn::PathArguments::Parenthesized(syn::ParenthesizedGenericArguments {
+                    paren_token: f.paren_token,
+                    inputs: f.inputs.iter().map(|arg| arg.ty.clone()).collect(),
+                    output: f.output.clone(),
+                }),
+            };
+            Ok(frozen_generics(&[segment], lazy))
+        }
+        syn::Type::Tuple(t) if t.elems.is_empty() => Ok((
+            quote! { <() as frozone::Freezable>::schema_with_context(ctx) },
+            lazy.freeze(ty),
+        )),
+        syn::Type::Tuple(t) => {
+            let (schemas, freezes): (Vec<_>, Vec<_>) = t
+                .elems
+                .iter()
+                .map(|elem| freeze_field_only_generics(elem, lazy))
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .unzip();
+            Ok((
+                quote! { frozone::Schema::Tuple([#(#schemas,)*].into()) },
+                quote! { frozone::hash::v1::tuple(&[#(#freezes,)*]) },
+            ))
+        }
+        syn::Type::Array(a) => {
+            let len = &a.len;
+            let (schema, freeze) = freeze_field_only_generics(&a.elem, lazy)?;
+            Ok((
+                quote! {
+                    frozone::Schema::Array {
+                        len: #len,
+                        item: (#schema).into(),
                     }
-                    // not sure how those would be constructed though
-                    syn::PathArguments::Parenthesized(parenthesized) => {
-                        let generic_output = match &parenthesized.output {
-                            syn::ReturnType::Default => quote! {
-                                 Box::new(|ctx| (
-                                    <() as Freezable>::freeze_with_context(ctx)
-                                )) as F
-                            },
-                            syn::ReturnType::Type(_, box_of_t) => {
-                                let inner_type = *box_of_t.clone();
-                                quote! {{
-                                    let x: F = Box::new(|ctx| (
-                                        <#inner_type as Freezable>::freeze_with_context(ctx)
-                                    ));
-                                    x
-                                }}
-                            }
-                        };
-                        let generic_input = parenthesized.inputs.iter().map(|t| {
-                            quote! {{
-                                let x: F = Box::new(|ctx| (
-                                    <#t as Freezable>::freeze_with_context(ctx)
-                                ));
-                                x
-                            }}
-                        });
+                },
+                quote! { frozone::hash::v1::array(#len, #freeze) },
+            ))
+        }
+        syn::Type::Slice(s) => container("[]", &s.elem, lazy),
+        syn::Type::Reference(r) => match (&*r.elem, r.mutability) {
+            (syn::Type::Slice(s), None) => container("&[]", &s.elem, lazy),
+            (elem, None) => container("&", elem, lazy),
+            (elem, Some(_)) => container("&mut", elem, lazy),
+        },
+        syn::Type::Ptr(p) => match p.mutability {
+            None => container("*const", &p.elem, lazy),
+            Some(_) => container("*mut", &p.elem, lazy),
+        },
+        syn::Type::Paren(p) => freeze_field_only_generics(&p.elem, lazy),
+        syn::Type::Group(g) => freeze_field_only_generics(&g.elem, lazy),
+        syn::Type::Never(_) => Ok((
+            quote! { frozone::Schema::Opaque("!".into()) },
+            quote! { frozone::hash::v1::opaque("!") },
+        )),
+        _ => Err(Error::new_spanned(
+            ty,
+            "#[assume_frozen(freeze_generics)] doesn't support this type",
+        )),
+    }
+}
 
-                        quote! {{
-                            let x: F = Box::new(|ctx| ({
-                                let mut hasher = core::hash::SipHasher::new();
-                                "GenericFunc".hash(&mut hasher); // prevent collisions with bracketed generics
-                                [#(#generic_input,)*].iter().for_each(|x: &F| {
-                                    x(ctx).hash(&mut hasher);
-                                });
-                                let out = #generic_output;
-                                out(ctx).hash(&mut hasher);
-                                hasher.finish()
-                            }));
-                            x
-                        }}
-                    }
-                    syn::PathArguments::None => quote! {
-                    // _ => quote! {
-                        {
-                            let mut hasher = core::hash::SipHasher::new();
-                            hasher.finish()
+/// `Schema::FrozenGenerics` of path segments, only frozen over their generic arguments
+fn frozen_generics<'a>(
+    segments: impl IntoIterator<Item = &'a syn::PathSegment>,
+    lazy: &mut Lazy,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let (type_segments, segment_freezes): (Vec<_>, Vec<_>) = segments
+        .into_iter()
+        .map(|ps| match &ps.arguments {
+            syn::PathArguments::AngleBracketed(bracketed) => {
+                let generics: Vec<_> = bracketed
+                    .args
+                    .iter()
+                    .filter_map(|g| match g {
+                        syn::GenericArgument::Type(t) => Some(t),
+                        _ => None,
+                    })
+                    .collect();
+                // `Item = T`
+                let bindings: Vec<_> = bracketed
+                    .args
+                    .iter()
+                    .filter_map(|g| match g {
+                        syn::GenericArgument::AssocType(a) => Some((a.ident.to_string(), &a.ty)),
+                        _ => None,
+                    })
+                    .collect();
+                let schemas = generics.iter().map(|t| {
+                    quote! { <#t as frozone::Freezable>::schema_with_context(ctx) }
+                });
+                let binding_schemas = bindings.iter().map(|(name, t)| {
+                    quote! { (#name.into(), <#t as frozone::Freezable>::schema_with_context(ctx)) }
+                });
+                let mut freezes: Vec<_> = generics.iter().map(|t| lazy.freeze(t)).collect();
+                freezes.extend(bindings.iter().map(|(name, t)| {
+                    let freeze = lazy.freeze(t);
+                    quote! { frozone::hash::v1::named(#name, #freeze) }
+                }));
+                (
+                    quote! {
+                        frozone::schema::GenericArgs::AngleBracketed {
+                            args: [#(#schemas,)*].into(),
+                            bindings: [#(#binding_schemas,)*].into(),
                         }
                     },
-                }
-            });
-            quote! {{
-                let x: NF = Box::new(|ctx|
-                    (
-                    stringify!(#name),
-                    {
-                        let mut hasher = core::hash::SipHasher::new();
+                    quote! {
+                        frozone::hash::v1::angle_bracketed_args(&[#(#freezes,)*])
+                    },
+                )
+            }
+            // `Fn(A) -> B`, from trait objects and fn pointers
+            syn::PathArguments::Parenthesized(parenthesized) => {
+                let unit: syn::Type = syn::parse_quote! { () };
+                let output = match &parenthesized.output {
+                    syn::ReturnType::Default => &unit,
+                    syn::ReturnType::Type(_, box_of_t) => &**box_of_t,
+                };
+                let generic_output = quote! {
+                    <#output as frozone::Freezable>::schema_with_context(ctx)
+                };
+                let output_freeze = lazy.freeze(output);
+                let generic_input = parenthesized.inputs.iter().map(|t| {
+                    quote! { <#t as frozone::Freezable>::schema_with_context(ctx) }
+                });
+                let input_freezes: Vec<_> = parenthesized
+                    .inputs
+                    .iter()
+                    .map(|t| lazy.freeze(t))
+                    .collect();
 
-                        [#(#type_segments,)*].iter().for_each(|x: &F| {
-                            x(ctx).hash(&mut hasher);
-                        });
-                        hasher.finish()
-                    })
-                );
-                x
-            }}
-        }
-        _ => {
-            panic!("type not a path");
-        }
-    }
+                (
+                    quote! {
+                        frozone::schema::GenericArgs::Parenthesized {
+                            inputs: [#(#generic_input,)*].into(),
+                            output: #generic_output.into(),
+                        }
+                    },
+                    quote! {
+                        frozone::hash::v1::parenthesized_args(
+                            &[#(#input_freezes,)*],
+                            #output_freeze,
+                        )
+                    },
+                )
+            }
+            syn::PathArguments::None => (
+                quote! { frozone::schema::GenericArgs::None },
+                quote! { frozone::hash::v1::no_generic_args() },
+            ),
+        })
+        .unzip();
+    (
+        quote! {
+            frozone::Schema::FrozenGenerics(
+                [#(#type_segments,)*].into()
+            )
+        },
+        quote! { frozone::hash::v1::frozen_generics(&[#(#segment_freezes,)*]) },
+    )
 }
 
 fn attr_helper_freeze_generics(attr: &syn::Attribute) -> bool {
@@ -358,25 +613,3 @@ fn attr_helper_freeze_generics(attr: &syn::Attribute) -> bool {
     // println!("found generic {found_freeze_generic:?}");
     found_freeze_generic
 }
-
-fn generics_to_unit(generics: &syn::Generics) -> proc_macro2::TokenStream {
-    if generics.params.is_empty() {
-        return quote! {};
-    }
-    let unit_args = generics.params.iter().filter_map(|param| {
-        match param {
-            // Transform Type parameters (e.g., T) to ()
-            syn::GenericParam::Type(_) => Some(quote! { () }),
-
-            // Transform Const parameters (e.g., const N: usize) to a value or ()
-            // Note: Const generics usually need a value, but in some type contexts () works
-            syn::GenericParam::Const(_) => Some(quote! { () }),
-
-            // Lifetimes are usually stripped when trying to reach a 'static-like'
-            // representation, as () satisfies 'static.
-            GenericParam::Lifetime(_) => None,
-        }
-    });
-
-    quote! { < #(#unit_args),* > }
-}
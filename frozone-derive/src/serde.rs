@@ -0,0 +1,501 @@
+//! `#[serde(...)]` attributes that change the serialized form of a type
+//!
+//! Only the attributes that matter to the freeze are read, the others are
+//! skipped (serde itself reports invalid ones)
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{Error, Result};
+
+/// `rename_all` rules, as implemented by serde
+#[derive(Clone, Copy)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(lit: &syn::LitStr) -> Result<Self> {
+        Ok(match lit.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => return Err(Error::new_spanned(lit, "unknown serde rename rule")),
+        })
+    }
+
+    /// variants are expected to be PascalCase
+    fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_string(),
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Camel => variant[..1].to_ascii_lowercase() + &variant[1..],
+            RenameRule::Snake => {
+                let mut snake = String::new();
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                snake
+            }
+            RenameRule::ScreamingSnake => RenameRule::Snake
+                .apply_to_variant(variant)
+                .to_ascii_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+        }
+    }
+
+    /// fields are expected to be snake_case
+    fn apply_to_field(self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_string(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            RenameRule::Camel => {
+                let pascal = RenameRule::Pascal.apply_to_field(field);
+                pascal[..1].to_ascii_lowercase() + &pascal[1..]
+            }
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+}
+
+/// attribute value that may differ when serializing and deserializing,
+/// e.g `rename = "a"` or `rename(serialize = "a", deserialize = "b")`
+pub struct SerDe<T> {
+    serialize: Option<T>,
+    deserialize: Option<T>,
+}
+
+impl<T> Default for SerDe<T> {
+    fn default() -> Self {
+        SerDe {
+            serialize: None,
+            deserialize: None,
+        }
+    }
+}
+
+impl<T: Clone> SerDe<T> {
+    fn parse(meta: &ParseNestedMeta, f: impl Fn(&syn::LitStr) -> Result<T>) -> Result<Self> {
+        let mut value = SerDe::default();
+        if meta.input.peek(syn::Token![=]) {
+            let lit: syn::LitStr = meta.value()?.parse()?;
+            let v = f(&lit)?;
+            value.serialize = Some(v.clone());
+            value.deserialize = Some(v);
+        } else {
+            meta.parse_nested_meta(|meta| {
+                let lit: syn::LitStr = meta.value()?.parse()?;
+                if meta.path.is_ident("serialize") {
+                    value.serialize = Some(f(&lit)?);
+                } else if meta.path.is_ident("deserialize") {
+                    value.deserialize = Some(f(&lit)?);
+                }
+                Ok(())
+            })?;
+        }
+        Ok(value)
+    }
+}
+
+/// skips the value of an attribute that doesn't matter to the freeze
+fn skip(meta: &ParseNestedMeta) -> Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|meta| skip(&meta))?;
+    }
+    Ok(())
+}
+
+fn parse_serde(
+    attrs: &[syn::Attribute],
+    mut f: impl FnMut(&ParseNestedMeta) -> Result<bool>,
+) -> Result<()> {
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| if f(&meta)? { Ok(()) } else { skip(&meta) })?;
+    }
+    Ok(())
+}
+
+/// `#[serde(...)]` attributes of a struct or enum
+#[derive(Default)]
+pub struct Container {
+    /// `rename_all`: applied to the fields of a struct, or the variants of an enum
+    rename_all: SerDe<RenameRule>,
+    /// `rename_all_fields`: `rename_all` of every variant
+    rename_all_fields: SerDe<RenameRule>,
+    /// `default`: missing fields of a struct are defaulted
+    default: bool,
+    transparent: bool,
+    /// `tag = "t"`
+    tag: Option<String>,
+    /// `content = "c"`, along with `tag`
+    content: Option<String>,
+    untagged: bool,
+}
+
+impl Container {
+    pub fn parse(attrs: &[syn::Attribute]) -> Result<Self> {
+        let mut container = Container::default();
+        parse_serde(attrs, |meta| {
+            if meta.path.is_ident("rename_all") {
+                container.rename_all = SerDe::parse(meta, RenameRule::parse)?;
+            } else if meta.path.is_ident("rename_all_fields") {
+                container.rename_all_fields = SerDe::parse(meta, RenameRule::parse)?;
+            } else if meta.path.is_ident("default") {
+                skip(meta)?;
+                container.default = true;
+            } else if meta.path.is_ident("transparent") {
+                container.transparent = true;
+            } else if meta.path.is_ident("tag") {
+                container.tag = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.path.is_ident("content") {
+                container.content = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.path.is_ident("untagged") {
+                container.untagged = true;
+            } else {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        Ok(container)
+    }
+
+    /// `frozone::schema::Representation` of the struct or enum
+    pub fn to_tokens(&self) -> TokenStream {
+        let repr = quote! { frozone::schema::Representation };
+        match (&self.tag, &self.content) {
+            (Some(tag), Some(content)) => quote! {
+                #repr::Adjacent { tag: #tag.into(), content: #content.into() }
+            },
+            (Some(tag), None) => quote! { #repr::Internal { tag: #tag.into() } },
+            // `content` without `tag` is rejected by serde
+            (None, _) if self.untagged => quote! { #repr::Untagged },
+            (None, _) if self.transparent => quote! { #repr::Transparent },
+            (None, _) => quote! { #repr::Default },
+        }
+    }
+
+    /// attributes applying to the fields of a struct
+    pub fn fields(&self) -> Fields<'_> {
+        Fields {
+            rename_all: &self.rename_all,
+            default: self.default,
+        }
+    }
+}
+
+/// attributes of a struct or variant that apply to its fields
+pub struct Fields<'a> {
+    rename_all: &'a SerDe<RenameRule>,
+    default: bool,
+}
+
+/// `#[serde(...)]` attributes of a variant
+pub struct Variant {
+    names: Names,
+    presence: Presence,
+    /// `rename_all` (or the enum's `rename_all_fields`), applied to its fields
+    rename_all: SerDe<RenameRule>,
+}
+
+impl Variant {
+    pub fn parse(
+        attrs: &[syn::Attribute],
+        ident: &syn::Ident,
+        container: &Container,
+    ) -> Result<Self> {
+        let mut names = Names::new(ident);
+        let mut presence = Presence::default();
+        let mut rename_all = SerDe::default();
+        parse_serde(attrs, |meta| {
+            if meta.path.is_ident("rename_all") {
+                rename_all = SerDe::parse(meta, RenameRule::parse)?;
+                Ok(true)
+            } else {
+                Ok(names.parse(meta)? || presence.parse(meta)?)
+            }
+        })?;
+        names.rename_all(&container.rename_all, RenameRule::apply_to_variant);
+        let rename_all = SerDe {
+            serialize: rename_all
+                .serialize
+                .or(container.rename_all_fields.serialize),
+            deserialize: rename_all
+                .deserialize
+                .or(container.rename_all_fields.deserialize),
+        };
+        Ok(Variant {
+            names,
+            presence,
+            rename_all,
+        })
+    }
+
+    /// attributes applying to the fields of the variant
+    pub fn fields(&self) -> Fields<'_> {
+        Fields {
+            rename_all: &self.rename_all,
+            default: false,
+        }
+    }
+
+    /// `frozone::schema::Serde` of the variant
+    pub fn to_tokens(&self) -> TokenStream {
+        to_tokens(&self.names, &self.presence, false)
+    }
+}
+
+/// `#[serde(...)]` attributes of a field
+pub struct Field {
+    names: Names,
+    presence: Presence,
+    flatten: bool,
+}
+
+impl Field {
+    pub fn parse(field: &syn::Field, fields: Fields) -> Result<Self> {
+        let mut names = match &field.ident {
+            Some(ident) => Names::new(ident),
+            // unnamed fields are serialized by position
+            None => Names::unnamed(),
+        };
+        let mut presence = Presence {
+            default: fields.default,
+            ..Presence::default()
+        };
+        let mut flatten = false;
+        parse_serde(&field.attrs, |meta| {
+            if meta.path.is_ident("flatten") {
+                flatten = true;
+                return Ok(true);
+            }
+            Ok(names.parse(meta)? || presence.parse(meta)?)
+        })?;
+        names.rename_all(fields.rename_all, RenameRule::apply_to_field);
+        Ok(Field {
+            names,
+            presence,
+            flatten,
+        })
+    }
+
+    /// `#[serde(skip)]`: the field is neither serialized nor deserialized,
+    /// so its type isn't frozen
+    pub fn skipped(&self) -> bool {
+        self.presence.skip_serializing && self.presence.skip_deserializing
+    }
+
+    /// `frozone::schema::Serde` of the field
+    pub fn to_tokens(&self) -> TokenStream {
+        to_tokens(&self.names, &self.presence, self.flatten)
+    }
+}
+
+/// `skip`-like attributes of a field or variant
+#[derive(Default)]
+struct Presence {
+    skip_serializing: bool,
+    skip_serializing_if: bool,
+    skip_deserializing: bool,
+    default: bool,
+}
+
+impl Presence {
+    fn parse(&mut self, meta: &ParseNestedMeta) -> Result<bool> {
+        if meta.path.is_ident("skip") {
+            self.skip_serializing = true;
+            self.skip_deserializing = true;
+        } else if meta.path.is_ident("skip_serializing") {
+            self.skip_serializing = true;
+        } else if meta.path.is_ident("skip_serializing_if") {
+            skip(meta)?;
+            self.skip_serializing_if = true;
+        } else if meta.path.is_ident("skip_deserializing") {
+            self.skip_deserializing = true;
+        } else if meta.path.is_ident("default") {
+            // `default` or `default = "path"`
+            skip(meta)?;
+            self.default = true;
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// `serialized` and `deserialized` fields of `frozone::schema::Serde`,
+    /// if not the default ones
+    fn to_tokens(&self) -> Option<(TokenStream, TokenStream)> {
+        let serialized = match (self.skip_serializing, self.skip_serializing_if) {
+            (true, _) => "Never",
+            (false, true) => "Conditionally",
+            (false, false) => "Always",
+        };
+        let deserialized = match (self.skip_deserializing, self.default) {
+            (true, _) => "Never",
+            (false, true) => "Defaulted",
+            (false, false) => "Required",
+        };
+        if (serialized, deserialized) == ("Always", "Required") {
+            return None;
+        }
+        let (serialized, deserialized) = (
+            syn::Ident::new(serialized, Span::call_site()),
+            syn::Ident::new(deserialized, Span::call_site()),
+        );
+        Some((
+            quote! { frozone::schema::Serialized::#serialized },
+            quote! { frozone::schema::Deserialized::#deserialized },
+        ))
+    }
+}
+
+/// `frozone::schema::Serde` of a field or variant
+fn to_tokens(names: &Names, presence: &Presence, flatten: bool) -> TokenStream {
+    let (serialize, deserialize, aliases) = names.names();
+    let presence = presence.to_tokens();
+    if serialize.is_none()
+        && deserialize.is_none()
+        && aliases.is_empty()
+        && presence.is_none()
+        && !flatten
+    {
+        return quote! { frozone::schema::Serde::default() };
+    }
+    let option = |name: Option<String>| match name {
+        Some(name) => quote! { Some(#name.into()) },
+        None => quote! { None },
+    };
+    let (serialize, deserialize) = (option(serialize), option(deserialize));
+    let (serialized, deserialized) = presence.unwrap_or_else(|| {
+        (
+            quote! { frozone::schema::Serialized::Always },
+            quote! { frozone::schema::Deserialized::Required },
+        )
+    });
+    quote! {
+        frozone::schema::Serde {
+            serialize_name: #serialize,
+            deserialize_name: #deserialize,
+            aliases: [#(#aliases.into(),)*].into(),
+            serialized: #serialized,
+            deserialized: #deserialized,
+            flatten: #flatten,
+        }
+    }
+}
+
+/// serialized names of a field or variant
+struct Names {
+    /// Rust name (as in the schema)
+    rust: Option<String>,
+    /// Rust name without `r#`, what serde uses by default
+    ident: Option<String>,
+    rename: SerDe<String>,
+    aliases: Vec<String>,
+}
+
+impl Names {
+    fn new(ident: &syn::Ident) -> Self {
+        let rust = ident.to_string();
+        let ident = rust.strip_prefix("r#").unwrap_or(&rust).to_string();
+        Names {
+            rust: Some(rust),
+            ident: Some(ident),
+            rename: SerDe::default(),
+            aliases: Vec::new(),
+        }
+    }
+
+    fn unnamed() -> Self {
+        Names {
+            rust: None,
+            ident: None,
+            rename: SerDe::default(),
+            aliases: Vec::new(),
+        }
+    }
+
+    fn parse(&mut self, meta: &ParseNestedMeta) -> Result<bool> {
+        if meta.path.is_ident("rename") {
+            let rename = SerDe::parse(meta, |lit| Ok(lit.value()))?;
+            self.rename.serialize = rename.serialize.or(self.rename.serialize.take());
+            self.rename.deserialize = rename.deserialize.or(self.rename.deserialize.take());
+        } else if meta.path.is_ident("alias") {
+            let alias: syn::LitStr = meta.value()?.parse()?;
+            self.aliases.push(alias.value());
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// applies `rename_all` to the names that aren't explicitly renamed
+    fn rename_all(&mut self, rule: &SerDe<RenameRule>, apply: fn(RenameRule, &str) -> String) {
+        let Some(ident) = &self.ident else {
+            return;
+        };
+        if self.rename.serialize.is_none() {
+            self.rename.serialize = rule.serialize.map(|r| apply(r, ident));
+        }
+        if self.rename.deserialize.is_none() {
+            self.rename.deserialize = rule.deserialize.map(|r| apply(r, ident));
+        }
+    }
+
+    /// (serialized name, deserialized name, aliases), the names being
+    /// only kept when they are not the Rust one
+    fn names(&self) -> (Option<String>, Option<String>, Vec<String>) {
+        let name = |rename: &Option<String>| {
+            rename
+                .as_ref()
+                .or(self.ident.as_ref())
+                .filter(|name| Some(*name) != self.rust.as_ref())
+                .cloned()
+        };
+        let mut aliases = self.aliases.clone();
+        aliases.sort();
+        aliases.dedup();
+        (
+            name(&self.rename.serialize),
+            name(&self.rename.deserialize),
+            aliases,
+        )
+    }
+}
@@ -0,0 +1,47 @@
+//! Errors met while freezing a type
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::fmt;
+
+use crate::internals::TYPE_RECURSION_MESSAGE;
+
+/// Error met while freezing a type, see [`Freezable::try_freeze`](crate::Freezable::try_freeze)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreezeError {
+    /// path from the frozen type to where freezing failed, in the format
+    /// of [`Change::path`](crate::schema::Change::path) (e.g `Frozone.power.level`)
+    pub path: String,
+    pub kind: FreezeErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FreezeErrorKind {
+    /// more than [`TYPE_RECURSION_LIMIT`](crate::TYPE_RECURSION_LIMIT) nested types
+    RecursionLimit,
+    /// a type that can't be frozen
+    Unsupported(Cow<'static, str>),
+    /// a type that can be frozen, but isn't allowed to
+    Policy(Cow<'static, str>),
+}
+
+impl fmt::Display for FreezeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FreezeErrorKind::RecursionLimit => f.write_str(TYPE_RECURSION_MESSAGE),
+            FreezeErrorKind::Unsupported(what) => write!(f, "unsupported: {}", what),
+            FreezeErrorKind::Policy(what) => write!(f, "not allowed: {}", what),
+        }
+    }
+}
+
+impl fmt::Display for FreezeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path.as_str() {
+            "" => write!(f, "could not freeze: {}", self.kind),
+            path => write!(f, "could not freeze {}: {}", path, self.kind),
+        }
+    }
+}
+
+impl core::error::Error for FreezeError {}
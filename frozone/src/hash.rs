@@ -0,0 +1,131 @@
+//! Hash algorithms the freezes are computed with
+//!
+//! Each freeze algorithm is versioned (see [`v1`]) and specified, so that
+//! pinned freezes can be recomputed independently of frozone, and never
+//! silently change when upgrading the crate: a given algorithm version
+//! always gives the same freeze for the same type definition, changes to
+//! the algorithm are released as a new version.
+//!
+//! Newer algorithms are opt-in (see [`v2`]), [`Freezable::freeze`](crate::Freezable::freeze)
+//! and [`Freezable::FREEZE`](crate::Freezable::FREEZE) always use v1.
+//!
+//! All versions are const-evaluable, and shared by [`Schema`](crate::Schema)
+//! and the compile-time [`Freezable::FREEZE`](crate::Freezable::FREEZE),
+//! so that both always agree.
+pub mod v1;
+pub mod v2;
+
+/// SipHash-2-4 (with zero keys) that can be used in const contexts,
+/// writing a `&str` writes its bytes then `0xff` (as `core::hash::Hash` does)
+///
+/// Unlike `core::hash::Hasher`, integers are always written as little-endian
+/// bytes, so that the hash doesn't depend on the target
+#[derive(Debug, Clone, Copy)]
+pub struct Hasher {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    /// unprocessed bytes (little endian)
+    tail: u64,
+    ntail: usize,
+    length: usize,
+}
+
+impl Hasher {
+    pub const fn new() -> Self {
+        Hasher {
+            v0: 0x736f6d6570736575,
+            v1: 0x646f72616e646f6d,
+            v2: 0x6c7967656e657261,
+            v3: 0x7465646279746573,
+            tail: 0,
+            ntail: 0,
+            length: 0,
+        }
+    }
+
+    const fn round(mut self) -> Self {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13);
+        self.v1 ^= self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16);
+        self.v3 ^= self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21);
+        self.v3 ^= self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17);
+        self.v1 ^= self.v2;
+        self.v2 = self.v2.rotate_left(32);
+        self
+    }
+
+    /// processes 8 bytes (2 compression rounds)
+    const fn compress(mut self, m: u64) -> Self {
+        self.v3 ^= m;
+        self = self.round().round();
+        self.v0 ^= m;
+        self
+    }
+
+    pub const fn write(mut self, bytes: &[u8]) -> Self {
+        let mut i = 0;
+        while i < bytes.len() {
+            self.tail |= (bytes[i] as u64) << (8 * self.ntail);
+            self.ntail += 1;
+            if self.ntail == 8 {
+                self = self.compress(self.tail);
+                self.tail = 0;
+                self.ntail = 0;
+            }
+            i += 1;
+        }
+        self.length = self.length.wrapping_add(bytes.len());
+        self
+    }
+
+    pub const fn write_str(self, s: &str) -> Self {
+        self.write(s.as_bytes()).write(&[0xff])
+    }
+
+    /// writes the little-endian bytes of `n` (whatever the target's endianness)
+    pub const fn write_u64(self, n: u64) -> Self {
+        self.write(&n.to_le_bytes())
+    }
+
+    /// writes the little-endian bytes of `n` (whatever the target's endianness)
+    pub const fn write_i32(self, n: i32) -> Self {
+        self.write(&n.to_le_bytes())
+    }
+
+    pub const fn write_u64s(mut self, ns: &[u64]) -> Self {
+        let mut i = 0;
+        while i < ns.len() {
+            self = self.write_u64(ns[i]);
+            i += 1;
+        }
+        self
+    }
+
+    pub const fn finish(self) -> u64 {
+        let b = ((self.length as u64 & 0xff) << 56) | self.tail;
+        let mut h = self.compress(b);
+        h.v2 ^= 0xff;
+        h = h.round().round().round().round();
+        h.v0 ^ h.v1 ^ h.v2 ^ h.v3
+    }
+}
+
+impl Default for Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SipHash-2-4 of `bytes`, with zero keys
+pub const fn siphash(bytes: &[u8]) -> u64 {
+    Hasher::new().write(bytes).finish()
+}
@@ -0,0 +1,201 @@
+//! Freeze algorithm v1
+//!
+//! This is the algorithm behind [`Freezable::freeze`](crate::Freezable::freeze),
+//! [`Freezable::FREEZE`](crate::Freezable::FREEZE) and [`Schema::freeze`](crate::Schema::freeze),
+//! it gives the same freezes as frozone 0.4.2 (built with rustc 1.95, on 64-bit
+//! little-endian targets) and will not change in any later version of frozone.
+//! There are two exceptions:
+//! - the fields with `#[serde(skip)]`: frozone 0.4.2 froze their type, their
+//!   schema is now [`Schema::AssumeFrozen`](crate::Schema::AssumeFrozen) so
+//!   that the type doesn't need to be `Freezable`
+//! - nested instantiations of a generic type, e.g `Wrapper<Wrapper<u8>>`:
+//!   frozone 0.4.2 froze the inner `Wrapper<u8>` as a back-edge to the outer
+//!   `Wrapper` (`Recursion { depth }`), they are now frozen as the distinct
+//!   types they are (as `Wrapper<u8>` is frozen on its own)
+//!
+//! The freezes don't depend on the target (pointer width, endianness) nor on
+//! the version of rustc used.
+//!
+//! # Specification
+//!
+//! A freeze is a `u64` computed from the [`Schema`](crate::Schema) of a type, with:
+//! - `siphash(bytes)`: SipHash-2-4 of `bytes` with a zero key (see [`siphash`](super::siphash))
+//! - `str(s)`: the UTF-8 bytes of `s` followed by `0xff`
+//! - `u64(n)`, `i32(n)`: the little-endian bytes of `n`
+//! - `+`: byte concatenation, `Σ`: wrapping (modulo 2^64) sum
+//!
+//! | schema | freeze |
+//! |--------|--------|
+//! | `Opaque(name)` | `siphash(str(name))` |
+//! | `Container { name, args }` | `siphash(str(name) + u64(freeze(arg)) for each arg)` |
+//! | `Array { len, item }` | `siphash(str("[;N]") + u64(len) + u64(freeze(item)))` |
+//! | `Tuple(items)` | `siphash(str("(,)") + len + u64(freeze(item)) for each item)`, with `len` being `i32(items.len())` for 1 and 2-tuples, `u64(1)` for longer ones |
+//! | `Struct { shape, fields }` | `Σ siphash(str(field.name) + u64(freeze(field.schema)))` over the fields, the names of the fields of `Shape::Tuple` structs all being `"_"` |
+//! | `Enum { variants }` | `Σ siphash(str(variant.name) + u64(variant(variant)))` over the variants |
+//! | `Recursion { depth }` | `depth + 1` |
+//! | `AssumeFrozen` | `0` |
+//! | `FrozenGenerics(segments)` | `siphash(u64(generics(segment)) for each segment)` |
+//!
+//! the content of a variant being:
+//!
+//! | variant | `variant(variant)` |
+//! |---------|--------------------|
+//! | `assume_frozen` without fields | `0` |
+//! | `assume_frozen` | `siphash(u64(freeze(field.schema)) for each field)` |
+//! | with a discriminant | `siphash(d + u64(siphash(d)) + u64(freeze(field.schema)) for each field)`, `d` being the `size` first little-endian bytes of the discriminant value (that is the size of its integer type, e.g 4 for `A = 1` that is an `i32`, and 8 for `isize` and `usize`) |
+//! | without discriminant | `siphash(i32(0) + u64(freeze(field.schema)) for each field)` |
+//!
+//! and the generic arguments of a path segment:
+//!
+//! | segment | `generics(segment)` |
+//! |---------|---------------------|
+//! | `None` | `siphash([])` |
+//! | `AngleBracketed { args, bindings }` | `siphash(str("GenericType") + u64(freeze(arg)) for each arg + u64(siphash(str(name) + u64(freeze(schema)))) for each (name, schema) binding)` |
+//! | `Parenthesized { inputs, output }` | `siphash(str("GenericFunc") + u64(freeze(input)) for each input + u64(freeze(output)))` |
+//!
+//! The schemas of the types implementing `Freezable` in frozone are part
+//! of the algorithm too (e.g `u64` is `Opaque("u64")`, `String` is
+//! `Opaque("alloc::string::String")` and `Vec<T>` is `Container { name: "Vec", args: [T] }`).
+//!
+//! # Test vectors
+//!
+//! ```rust
+//! use frozone::hash::{siphash, v1};
+//! use frozone::{Freezable, Schema};
+//!
+//! assert_eq!(siphash(b""), 0x1e924b9d737700d7);
+//! assert_eq!(siphash(b"frozone"), 0xade008bbeebd6ff1);
+//!
+//! let vectors = [
+//!     (r#"(opaque "u64")"#, 4711862451334276647),
+//!     (r#"(container "Vec" (opaque "u8"))"#, 10020407535702056423),
+//!     (r#"(array 3 (opaque "u8"))"#, 16095293787101914963),
+//!     (r#"(tuple (opaque "u8") (opaque "u16"))"#, 3829208944095917153),
+//!     (r#"(tuple (opaque "u8") (opaque "u16") (opaque "u32"))"#, 4724993029145050774),
+//!     (r#"(struct "MyType" named (field "field" (opaque "u64")))"#, 995647931575951812),
+//!     (r#"(struct "MyType" tuple (field "0" (opaque "u64")) (field "1" (assume_frozen)))"#, 2000929249945174383),
+//!     (r#"(enum "MyType" (variant "A" unit) (variant "B" unit (discriminant 2 4)))"#, 7524325567485655827),
+//!     (r#"(enum "MyType" (variant "A" tuple (field "0" (recursion "MyType" 1))) (variant "B" unit assume_frozen))"#, 6349873066777097421),
+//!     (r#"(struct "MyType" named (field "a" (frozen_generics (none) (angle_bracketed (opaque "u8")))))"#, 367806000789851696),
+//!     (r#"(struct "MyType" named (field "a" (frozen_generics (none) (angle_bracketed (opaque "u8") (binding "Item" (opaque "u16"))))))"#, 1565025617798723259),
+//! ];
+//! for (schema, freeze) in vectors {
+//!     assert_eq!(schema.parse::<Schema>().unwrap().freeze(), freeze, "{}", schema);
+//! }
+//! ```
+use super::Hasher;
+use crate::schema::Discriminant;
+
+/// freeze of a type frozen by its name only
+pub const fn opaque(name: &str) -> u64 {
+    Hasher::new().write_str(name).finish()
+}
+
+/// freeze of a generic container, from the freezes of its generic arguments
+pub const fn container(name: &str, args: &[u64]) -> u64 {
+    Hasher::new().write_str(name).write_u64s(args).finish()
+}
+
+pub const fn array(len: usize, item: u64) -> u64 {
+    Hasher::new()
+        .write_str("[;N]")
+        .write_u64(len as u64)
+        .write_u64(item)
+        .finish()
+}
+
+pub const fn tuple(items: &[u64]) -> u64 {
+    // prevent collisions with other container types
+    let h = Hasher::new().write_str("(,)");
+    let h = if items.len() <= 2 {
+        // 1 and 2-tuples have historically hashed their length as an i32
+        h.write_i32(items.len() as i32)
+    } else {
+        // ... and bigger ones the length of a 1-element array (as a 64-bit usize)
+        h.write_u64(1)
+    };
+    h.write_u64s(items).finish()
+}
+
+/// freeze of a (name, sub-freeze) pair, such as a field or a variant
+pub const fn named(name: &str, freeze: u64) -> u64 {
+    Hasher::new().write_str(name).write_u64(freeze).finish()
+}
+
+/// freeze of a struct (from its fields) or an enum (from its variants),
+/// summed so that their order doesn't matter
+pub const fn sum_named(items: &[(&str, u64)]) -> u64 {
+    let mut sum = 0u64;
+    let mut i = 0;
+    while i < items.len() {
+        sum = sum.wrapping_add(named(items[i].0, items[i].1));
+        i += 1;
+    }
+    sum
+}
+
+/// freeze of the content (discriminant and fields) of a variant
+pub const fn variant(
+    discriminant: Option<Discriminant>,
+    assume_frozen: bool,
+    fields: &[u64],
+) -> u64 {
+    let mut h = Hasher::new();
+    if assume_frozen {
+        // only the generics of the variant's fields may still be frozen
+        if fields.is_empty() {
+            return 0;
+        }
+    } else {
+        match discriminant {
+            Some(d) => {
+                // bytes of the discriminant value, as its original integer type
+                let bytes = d.value.to_le_bytes();
+                let bytes = bytes.split_at(d.size as usize).0;
+                h = h.write(bytes);
+                h = h.write_u64(Hasher::new().write(bytes).finish());
+            }
+            None => h = h.write_i32(0),
+        }
+    }
+    h.write_u64s(fields).finish()
+}
+
+/// loop detected: the depth between the first occurence and now
+/// is a 'unique enough' value
+pub const fn recursion(depth: u32) -> u64 {
+    depth as u64 + 1
+}
+
+/// freeze of an `#[assume_frozen]` field
+pub const ASSUME_FROZEN: u64 = 0;
+
+/// freeze of an `#[assume_frozen(freeze_generics)]` field,
+/// from the freeze of the generic arguments of each path segment
+pub const fn frozen_generics(segments: &[u64]) -> u64 {
+    Hasher::new().write_u64s(segments).finish()
+}
+
+/// `std` or `vec` in `std::vec::Vec<T>`
+pub const fn no_generic_args() -> u64 {
+    Hasher::new().finish()
+}
+
+/// `<T>` in `std::vec::Vec<T>`
+pub const fn angle_bracketed_args(args: &[u64]) -> u64 {
+    // prevent collisions with parenthesized generics
+    Hasher::new()
+        .write_str("GenericType")
+        .write_u64s(args)
+        .finish()
+}
+
+/// `(A, B) -> C` in `Fn(A, B) -> C`
+pub const fn parenthesized_args(inputs: &[u64], output: u64) -> u64 {
+    // prevent collisions with bracketed generics
+    Hasher::new()
+        .write_str("GenericFunc")
+        .write_u64s(inputs)
+        .write_u64(output)
+        .finish()
+}
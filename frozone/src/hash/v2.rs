@@ -0,0 +1,212 @@
+//! Freeze algorithm v2, opt-in through [`Freezable::freeze_v2`](crate::Freezable::freeze_v2)
+//! and [`Schema::freeze_v2`](crate::Schema::freeze_v2)
+//!
+//! **v2 is not finalized yet**: its freezes may still change until the next
+//! release of frozone, after which they won't. Don't pin them in the meantime.
+//!
+//! # Specification
+//!
+//! Same as [v1](super::v1), except for:
+//!
+//! | schema | freeze |
+//! |--------|--------|
+//! | `Struct { shape, fields, serde }` | `represented(serde, shaped(shape, Σ siphash(str(serialized(field)) + u64(content(field)))))` over the fields (without `shaped` for `Representation::Transparent` structs), the names of the fields of `Shape::Tuple` structs all being `"_"`, and the ones of `Representation::Transparent` structs `""` |
+//! | `Enum { variants, serde }` | `represented(serde, Σ siphash(str(serialized(variant)) + u64(content(variant))))` over the variants, with `shaped(variant.shape, variant(variant))` as the freeze |
+//! | `Struct { positional: true, .. }`, `Enum { positional: true, .. }`, and `Struct { shape: Shape::Tuple, .. }` (unless `Representation::Transparent`) | the same, with `siphash(str("Positional") + u64(position) + u64(siphash(str(serialized(item)) + u64(content(item)))) for each item)` instead of the `Σ`, the items being in their declaration order, and `position` being the index of the field among the non-skipped ones, or the index of the variant among all of them |
+//! | `Recursion { depth }` | `siphash(str("Recursion") + u64(distance))`, `distance` being the number of structs and enums from the type the back-edge points to down to (and including) the one the back-edge is in |
+//!
+//! where `shaped(shape, freeze)` is `freeze` for `Shape::Named`, and
+//! `siphash(str("Shape") + [kind] + u64(freeze))` for `Shape::Unit` (`kind` 0)
+//! and `Shape::Tuple` (`kind` 1): `struct A;`, `struct A()` and `struct A {}`
+//! have different freezes, as do the variants `V`, `V()` and `V {}`.
+//!
+//! the serde names of a field or variant (see [`Serde`](crate::schema::Serde))
+//! being frozen, rather than its Rust name (that is still used for the fields
+//! of `Shape::Tuple` structs, and when not renamed):
+//!
+//! | | |
+//! |-|-|
+//! | `serialized(f)` | `f.serde.serialize_name`, or `f.name` |
+//! | `deserialized(f)` | `f.serde.deserialize_name`, or `f.name` |
+//! | `presence(f)` | `freeze(f.schema)` if `f.serde` has the default `serialized` and `deserialized`, else `siphash(str("Presence") + [serialized] + [deserialized] + u64(freeze(f.schema)))`, `[serialized]` being the byte `0` for `Always`, `1` for `Conditionally` and `2` for `Never`, and `[deserialized]` `0` for `Required`, `1` for `Defaulted` and `2` for `Never` |
+//! | `content(f)` | `presence(f)` if `deserialized(f) == serialized(f)` and there are no `f.serde.aliases`, else `siphash(str("Deserialize") + str(deserialized(f)) + str(alias) for each alias + u64(presence(f)))` |
+//!
+//! A flattened field (`f.serde.flatten`) is frozen as `siphash(str("") + u64(siphash(str("Flatten") + u64(presence(f)))))`
+//! instead, its names not being serialized.
+//!
+//! The [`Representation`](crate::schema::Representation) of a struct or enum is frozen as:
+//!
+//! | `serde` | `represented(serde, freeze)` |
+//! |---------|------------------------------|
+//! | `Default` | `freeze` |
+//! | `Transparent` | `siphash(str("Representation") + [1] + str("") + str("") + u64(freeze))` |
+//! | `Internal { tag }` | `siphash(str("Representation") + [2] + str(tag) + str("") + u64(freeze))` |
+//! | `Adjacent { tag, content }` | `siphash(str("Representation") + [3] + str(tag) + str(content) + u64(freeze))` |
+//! | `Untagged` | `siphash(str("Representation") + [4] + str("") + str("") + u64(freeze))` |
+//!
+//! Under [`Profile::Layout`](crate::schema::Profile::Layout) (and only then,
+//! as it depends on the target), the freeze of a struct or enum with a
+//! [`Layout`](crate::schema::Layout) is `siphash(str("Layout") + str(layout.repr) + u64(layout.size) + u64(layout.align) + u64(siphash(str(field.name) + u64(offset))) for each field and offset + u64(freeze))`,
+//! `freeze` being its freeze as above (the fields of enums having no offsets).
+//!
+//! Skipped fields and variants (see [`Serde::is_skipped`](crate::schema::Serde::is_skipped))
+//! are left out of the sums, and the fields of a `Shape::Tuple` variant are
+//! frozen as `presence(field)` (the skipped ones being left out too). The
+//! fields of a `Shape::Named` variant are frozen by name, as the ones of a
+//! struct: `variant(variant)` is given the single freeze
+//! `Σ siphash(str(serialized(field)) + u64(content(field)))` (or its
+//! `Positional` form in positional enums) as fields, unless the variant has none.
+//!
+//! The freeze of a recursive type doesn't depend on the depth it was first
+//! found at (as the v1 `depth + 1` does): a type contributes the same freeze
+//! wherever it is embedded (as long as it isn't part of a cycle through what
+//! embeds it). The length of the cycles is still frozen: `A { b: Box<B> }` and
+//! `B { a: Option<A> }` don't have the freeze of `C { c: Option<Box<C>> }`.
+//!
+//! The cycles are frozen as declared, not minimized: minimizing the type graph
+//! would give `A { a: Option<Box<A>> }` and its unrollings over two or three
+//! types (`Cycle2_1` and `Cycle3_1` in the README) the same freeze, when the
+//! latter two have to differ. The freeze of a type is independent of where it
+//! is embedded, not of how its cycles are declared.
+//!
+//! # Test vectors
+//!
+//! ```rust
+//! use frozone::Schema;
+//! use frozone::hash::v2;
+//!
+//! let vectors = [
+//!     (r#"(struct "List" named (field "next" (container "Option" (container "Box" (recursion "List" 1)))))"#, 12641436022063495027),
+//!     (r#"(struct "A" named (field "b" (container "Box" (struct "B" named (field "a" (container "Option" (recursion "A" 1)))))))"#, 4863181340252400161),
+//!     (r#"(struct "Outer" named (field "list" (struct "List" named (field "next" (container "Option" (container "Box" (recursion "List" 2)))))))"#, 9946207253459469686),
+//!     (r#"(struct "MyType" named (field "my_field" (serde (serialize "myField") (deserialize "myField")) (opaque "u64")))"#, 8138175639492954286),
+//!     (r#"(struct "MyType" named (field "field" (serde (alias "old")) (opaque "u64")))"#, 1034115236171739483),
+//!     (r#"(struct "MyType" named (field "field" (serde (skip_serializing_if) (default)) (opaque "u64")) (field "cache" (serde (skip_serializing) (skip_deserializing)) (assume_frozen)))"#, 17857724284336396427),
+//!     (r#"(enum "MyType" (serde (tag "type")) (variant "A" unit) (variant "B" named (field "b" (serde (flatten)) (opaque "u64"))))"#, 14250643581497743860),
+//!     (r#"(struct "MyType" named (serde (transparent)) (field "a" (opaque "u64")))"#, 4340374736227690494),
+//!     (r#"(struct "MyType" named positional (field "a" (opaque "u8")) (field "b" (opaque "u16")))"#, 16879065614824491821),
+//!     (r#"(struct "MyType" unit)"#, 12791615952881758140),
+//!     (r#"(struct "MyType" tuple)"#, 8458812867653140266),
+//!     (r#"(struct "MyType" tuple (field "0" (opaque "u32")) (field "1" (opaque "u8")))"#, 13200410541165666946),
+//! ];
+//! for (schema, freeze) in vectors {
+//!     assert_eq!(schema.parse::<Schema>().unwrap().freeze_v2(), freeze, "{}", schema);
+//! }
+//! // `List` has the same freeze embedded in `Outer`
+//! assert_eq!(v2::named("list", 12641436022063495027), 9946207253459469686);
+//! // a renamed field is frozen as its serialized name
+//! assert_eq!(v2::sum_named(&[("myField", v2::opaque("u64"))]), 8138175639492954286);
+//!
+//! // layouts are only frozen by the layout profile
+//! use frozone::schema::Profile;
+//! let schema: Schema = r#"(struct "MyType" named (layout "C" 8 4 0 4) (field "a" (opaque "u8")) (field "b" (opaque "u32")))"#
+//!     .parse()
+//!     .unwrap();
+//! assert_eq!(schema.freeze_with(Profile::Layout), 11035755770651720804);
+//! assert_eq!(schema.freeze_v2(), 15398685935827280492);
+//! ```
+use super::Hasher;
+
+pub use super::v1::{
+    ASSUME_FROZEN, angle_bracketed_args, array, container, frozen_generics, named, no_generic_args,
+    opaque, parenthesized_args, sum_named, tuple, variant,
+};
+
+/// content of a field or variant deserialized as `name` (or `aliases`),
+/// that is not its serialized name
+pub const fn deserialized_as(name: &str, aliases: &[&str], freeze: u64) -> u64 {
+    let mut h = Hasher::new().write_str("Deserialize").write_str(name);
+    let mut i = 0;
+    while i < aliases.len() {
+        h = h.write_str(aliases[i]);
+        i += 1;
+    }
+    h.write_u64(freeze).finish()
+}
+
+/// content of a field or variant that isn't always (de)serialized,
+/// see [`Serialized`](crate::schema::Serialized) and [`Deserialized`](crate::schema::Deserialized)
+pub const fn presence(serialized: u8, deserialized: u8, freeze: u64) -> u64 {
+    Hasher::new()
+        .write_str("Presence")
+        .write(&[serialized, deserialized])
+        .write_u64(freeze)
+        .finish()
+}
+
+/// content of a `#[serde(flatten)]` field
+pub const fn flattened(freeze: u64) -> u64 {
+    Hasher::new()
+        .write_str("Flatten")
+        .write_u64(freeze)
+        .finish()
+}
+
+/// freeze of a struct or enum with a non-default serde representation
+/// (`kind` being 1 for transparent, 2 for internally tagged, 3 for
+/// adjacently tagged and 4 for untagged), see [`Representation`](crate::schema::Representation)
+pub const fn representation(kind: u8, tag: &str, content: &str, freeze: u64) -> u64 {
+    Hasher::new()
+        .write_str("Representation")
+        .write(&[kind])
+        .write_str(tag)
+        .write_str(content)
+        .write_u64(freeze)
+        .finish()
+}
+
+/// freeze of a unit (`kind` 0) or tuple (`kind` 1) struct or variant, whose
+/// fields freeze to `freeze`, see [`Shape`](crate::schema::Shape)
+pub const fn shaped(kind: u8, freeze: u64) -> u64 {
+    Hasher::new()
+        .write_str("Shape")
+        .write(&[kind])
+        .write_u64(freeze)
+        .finish()
+}
+
+/// freeze of a `#[frozone(positional)]` struct (from its fields) or enum
+/// (from its variants), `(position, name, freeze)`, in their order
+pub const fn positional(items: &[(u64, &str, u64)]) -> u64 {
+    let mut h = Hasher::new().write_str("Positional");
+    let mut i = 0;
+    while i < items.len() {
+        h = h
+            .write_u64(items[i].0)
+            .write_u64(named(items[i].1, items[i].2));
+        i += 1;
+    }
+    h.finish()
+}
+
+/// freeze under [`Profile::Layout`](crate::schema::Profile::Layout) of a
+/// `#[repr(...)]` struct or enum whose freeze is `freeze`, `offsets` being
+/// the `(name, offset)` of its fields, see [`Layout`](crate::schema::Layout)
+pub const fn layout(
+    repr: &str,
+    size: u64,
+    align: u64,
+    offsets: &[(&str, u64)],
+    freeze: u64,
+) -> u64 {
+    let mut h = Hasher::new()
+        .write_str("Layout")
+        .write_str(repr)
+        .write_u64(size)
+        .write_u64(align);
+    let mut i = 0;
+    while i < offsets.len() {
+        h = h.write_u64(named(offsets[i].0, offsets[i].1));
+        i += 1;
+    }
+    h.write_u64(freeze).finish()
+}
+
+/// loop detected: `distance` structs and enums between the type the
+/// back-edge points to and the back-edge (1 for a type embedding itself)
+pub const fn recursion(distance: u32) -> u64 {
+    Hasher::new()
+        .write_str("Recursion")
+        .write_u64(distance as u64)
+        .finish()
+}
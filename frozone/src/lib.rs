@@ -1,13 +1,21 @@
 #![cfg_attr(not(feature = "std"), no_std)]
+mod error;
+pub mod hash;
+#[cfg(feature = "std")]
+pub mod lock;
+pub mod schema;
 mod types;
 
+pub use error::{FreezeError, FreezeErrorKind};
 pub use frozone_derive::Freezable;
+pub use schema::Schema;
 
-#[cfg(not(feature = "std"))]
 extern crate alloc;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
+/// Maximum number of nested `#[derive(Freezable)]` types, past which
+/// freezing fails (see [`Freezable::try_freeze`]) instead of overflowing the stack
 pub const TYPE_RECURSION_LIMIT: usize = 1024;
 
 /// The main trait provided by this crate
@@ -15,13 +23,83 @@ pub const TYPE_RECURSION_LIMIT: usize = 1024;
 /// change (semantically speaking) from version to version by comparing
 /// their `::freeze()` to a known value
 pub trait Freezable {
+    /// The freeze, computed at compile-time: `T::FREEZE == T::freeze()`,
+    /// so that a changed structure can fail `cargo build`, e.g with
+    /// `const _: () = assert!(MyType::FREEZE == 12298013273002774775);`
+    ///
+    /// Type-recursive types (see the README) can't be frozen at compile-time
+    /// and fail to compile with a cycle error when their `FREEZE` is used, as
+    /// do types with `#[frozone(with)]` fields
+    const FREEZE: u64 = Self::FREEZE_V1;
+
+    /// [`Freezable::FREEZE`] with the algorithm v1 (see [`hash::v1`])
+    ///
+    /// Manual impls that don't override it have no compile-time freeze:
+    /// using their `FREEZE` fails to compile
+    const FREEZE_V1: u64 = internals::no_const_freeze_v1();
+
     /// Computes a 'hash of the type, subtypes, field names etc...'
     /// If the freeze doesn't change, you can be sure that the structure
     /// will have the same fields, serialize/deserialize to/from the same string
     /// (assuming your serializer itself doesn't change ofc) ...
+    ///
+    /// The freeze is computed with the algorithm v1, which `freeze()` will
+    /// keep using in later versions of frozone (new algorithms being opt-in)
+    ///
+    /// # Panics
+    /// if the type can't be frozen, see [`Freezable::try_freeze`]
     fn freeze() -> u64 {
+        Self::try_freeze().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// [`Freezable::freeze`], that fails instead of panicking (e.g when
+    /// the type nests more than [`TYPE_RECURSION_LIMIT`] types)
+    fn try_freeze() -> Result<u64, FreezeError> {
+        let mut ctx = FreezeCtx::default();
+        let freeze = Self::freeze_with_context(&mut ctx);
+        ctx.finish(freeze)
+    }
+
+    /// Computes the freeze with the algorithm v1 (see [`hash::v1`]),
+    /// the same as `freeze()` but explicit about the algorithm version
+    fn freeze_v1() -> u64 {
+        Self::schema().freeze_v1()
+    }
+
+    /// Computes the freeze with the algorithm v2 (see [`hash::v2`]), that is
+    /// opt-in and not finalized yet
+    fn freeze_v2() -> u64 {
+        Self::schema().freeze_v2()
+    }
+
+    /// Computes the freeze with the algorithm v2, the types equivalent
+    /// under `profile` having the same freeze (see [`schema::Profile`]),
+    /// `freeze_with(Profile::Strict)` being `freeze_v2()`
+    fn freeze_with(profile: schema::Profile) -> u64 {
+        Self::schema().freeze_with(profile)
+    }
+
+    /// Computes the freeze with the algorithm v2, the order of the fields
+    /// and variants of every struct and enum being frozen (as if they all
+    /// were `#[frozone(positional)]`, see [`Schema::positional`])
+    fn freeze_positional() -> u64 {
+        Self::schema().positional().freeze_v2()
+    }
+
+    /// Structural description of the type (fields, variants, containers...)
+    /// the freeze is computed from: `T::schema().freeze() == T::freeze()`
+    ///
+    /// # Panics
+    /// if the type can't be frozen, see [`Freezable::try_schema`]
+    fn schema() -> Schema {
+        Self::try_schema().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// [`Freezable::schema`], that fails instead of panicking
+    fn try_schema() -> Result<Schema, FreezeError> {
         let mut ctx = FreezeCtx::default();
-        Self::freeze_with_context(&mut ctx)
+        let schema = Self::schema_with_context(&mut ctx);
+        ctx.finish(schema)
     }
 
     /// Useful for debug (e.g inspecting what part of your structure
@@ -29,73 +107,271 @@ pub trait Freezable {
     /// prints (std::println!) frozone hashes along the structure's definition
     #[cfg(feature = "std")]
     fn display() {
-        let mut ctx = FreezeCtx {
-            cache: Vec::new(),
-            depth: 0,
-            display: true,
-        };
-        Self::freeze_with_context(&mut ctx);
+        struct Stdout;
+        impl core::fmt::Write for Stdout {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                std::print!("{}", s);
+                Ok(())
+            }
+        }
+        let _ = Self::display_to(Stdout);
+    }
+
+    /// Writes what [`Freezable::display`] prints to `out` (e.g a `String`),
+    /// also available without the `std` feature. Other outputs (loggers...)
+    /// can be plugged with [`Schema::visit`]
+    fn display_to(out: impl core::fmt::Write) -> core::fmt::Result {
+        Self::schema().visit(&mut schema::Printer(out))
     }
 
     /// actual entry point, useful to break frozone but otherwise
     /// shouldn't be used. `::freeze()` is the better choice in 100% of the cases
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64;
+    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
+        Self::schema_with_context(ctx).freeze()
+    }
+
+    /// builds the [`Schema`] of the type, what `#[derive(Freezable)]` implements
+    /// (errors are reported through [`FreezeCtx::fail`])
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema;
 }
 
+/// State of the freezing of a type graph, threaded through
+/// [`Freezable::schema_with_context`]
 #[derive(Debug, Default)]
 pub struct FreezeCtx {
     // type, depth where type was found at
-    pub cache: Vec<(core::any::TypeId, u32)>,
-    pub depth: u32,
-    pub display: bool,
+    cache: Vec<(TypeKey, u32)>,
+    depth: u32,
+    /// path from the root type to the field being frozen, e.g `Frozone`, `.power`
+    path: Vec<&'static str>,
+    /// first error met, the rest of the type graph is then skipped
+    error: Option<FreezeError>,
+}
+
+impl FreezeCtx {
+    /// Enters the derived type `name` (as `#[derive(Freezable)]` does), unless
+    /// it is a back-edge to a type being frozen, or freezing failed: returns
+    /// the schema to use instead of the type's own then
+    pub fn enter(&mut self, key: TypeKey, name: &'static str) -> Option<Schema> {
+        if self.error.is_some() {
+            return Some(Schema::AssumeFrozen);
+        }
+        if let Some((_t, first_depth)) = self.cache.iter().find(|(t, _d)| *t == key) {
+            // loop detected ! stop recursion and leave a back-edge to the first occurence
+            return Some(Schema::Recursion {
+                name: name.into(),
+                depth: *first_depth,
+            });
+        }
+        if self.path.is_empty() {
+            self.path.push(name);
+        }
+        if self.depth as usize >= TYPE_RECURSION_LIMIT {
+            self.fail(FreezeErrorKind::RecursionLimit);
+            return Some(Schema::AssumeFrozen);
+        }
+        self.depth += 1;
+        self.cache.push((key, self.depth));
+        None
+    }
+
+    /// Leaves the type last entered with [`FreezeCtx::enter`]
+    pub fn leave(&mut self) {
+        self.cache.pop();
+        self.depth -= 1;
+        if self.depth == 0 {
+            self.path.clear();
+        }
+    }
+
+    /// Builds the schema of the field at `segment` (e.g `.level` or `::Variant.0`)
+    pub fn at(
+        &mut self,
+        segment: &'static str,
+        schema: impl FnOnce(&mut Self) -> Schema,
+    ) -> Schema {
+        self.path.push(segment);
+        let schema = schema(self);
+        self.path.pop();
+        schema
+    }
+
+    /// Stops freezing, failing at the current path (only the first error is kept)
+    pub fn fail(&mut self, kind: FreezeErrorKind) {
+        if self.error.is_none() {
+            self.error = Some(FreezeError {
+                path: self.path.concat(),
+                kind,
+            });
+        }
+    }
+
+    /// `value` if freezing didn't fail
+    pub fn finish<T>(self, value: T) -> Result<T, FreezeError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(value),
+        }
+    }
+}
+
+/// Identity of a type (instantiation) for the detection of type-recursion
+///
+/// `Wrapper<Wrapper<u8>>` embeds two different types, and generic types
+/// aren't necessarily `'static` (so have no `TypeId`): a type is identified by
+/// the `TypeId` of a marker type unique to its `#[derive(Freezable)]`, and the
+/// name of the instantiation (that tells the generic arguments apart, lifetimes
+/// being erased)
+///
+/// `type_name` isn't guaranteed to be unique: two instantiations of a type whose
+/// arguments have the same name (e.g the same type from two versions of a crate)
+/// share a key, the one nested in the other being frozen as a back-edge to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeKey {
+    pub derive: core::any::TypeId,
+    pub name: &'static str,
+}
+
+impl TypeKey {
+    /// key of `T`, derived with the marker type `M`
+    pub fn of<M: 'static, T: ?Sized>() -> Self {
+        TypeKey {
+            derive: core::any::TypeId::of::<M>(),
+            name: core::any::type_name::<T>(),
+        }
+    }
 }
 
 /// internals to reuse from frozone-derive
 /// to simplify the code in the proc-macro
 pub mod internals {
     pub use super::*;
-    pub use core::hash::{Hash, Hasher};
 
     pub const TYPE_RECURSION_MESSAGE: &str = "exceeded the 1024 nested types limit";
 
-    #[cfg(not(feature = "std"))]
-    extern crate alloc;
-    #[cfg(not(feature = "std"))]
-    pub use alloc::boxed::Box;
-
-    /// "Name and Freeze"-returning function
-    pub type NF = Box<dyn Fn(&mut FreezeCtx) -> (&str, u64)>;
-    /// "(only) Freeze"-returning function
-    pub type F = Box<dyn Fn(&mut FreezeCtx) -> u64>;
-
-    pub fn nf_freeze(x: &NF, ctx: &mut FreezeCtx, acc: u64) -> u64 {
-        #[allow(unused_variables)]
-        let (display, depth) = (ctx.display, ctx.depth as usize);
-        #[allow(deprecated)]
-        let mut hasher = core::hash::SipHasher::new();
-        let y = x(ctx);
-
-        #[cfg(feature = "std")]
-        if display {
-            println!("{:\t<3$} - {} : {:#018x}", "", y.0, y.1, depth - 1);
+    /// context of a type nested in `depth` other types, to test the
+    /// recursion limit
+    pub fn nested_ctx(depth: u32) -> FreezeCtx {
+        FreezeCtx {
+            depth,
+            ..Default::default()
         }
-        y.0.hash(&mut hasher);
-        y.1.hash(&mut hasher);
-        acc.overflowing_add(hasher.finish()).0
     }
 
-    pub fn f_freeze(x: &F, ctx: &mut FreezeCtx, acc: u64) -> u64 {
-        #[allow(unused_variables)]
-        let (display, depth) = (ctx.display, ctx.depth as usize);
-        #[allow(deprecated)]
-        let mut hasher = core::hash::SipHasher::new();
-        let y = x(ctx);
+    /// integer types of enum discriminants, along with their size in the
+    /// freeze (that doesn't depend on the target's pointer width)
+    pub trait DiscriminantType {
+        const SIZE: u8;
+    }
 
-        #[cfg(feature = "std")]
-        if display {
-            println!("{:\t<2$} : {:#x}", "", y, depth);
-        }
-        y.hash(&mut hasher);
-        acc.overflowing_add(hasher.finish()).0
+    macro_rules! discriminant_type {
+        ($($t:ty => $size:literal),*) => {
+            $(impl DiscriminantType for $t {
+                const SIZE: u8 = $size;
+            })*
+        };
+    }
+    discriminant_type!(
+        i8 => 1, i16 => 2, i32 => 4, i64 => 8, i128 => 16, isize => 8,
+        u8 => 1, u16 => 2, u32 => 4, u64 => 8, u128 => 16, usize => 8
+    );
+
+    pub const fn discriminant_size<T: DiscriminantType>(_discriminant: &T) -> u8 {
+        T::SIZE
+    }
+
+    /// compile-time freeze of a derived type, computed by a type generic over
+    /// `M: LazyFreeze<..>`: `#[derive(Freezable)]` impls aren't generic, so
+    /// that otherwise their `FREEZE_V1` would be evaluated eagerly, failing on
+    /// recursive types even when unused
+    pub trait ConstFreeze {
+        const FREEZE_V1: u64;
+    }
+
+    /// compile-time freeze of the fields whose schema is built by a function
+    /// (`#[frozone(with)]`), that fails the const evaluation of `FREEZE_V1`
+    pub const fn no_const_freeze() -> u64 {
+        panic!("#[frozone(with)] fields can't be frozen at compile-time")
+    }
+
+    /// `FREEZE_V1` of a derived type, through a call so that it is only
+    /// evaluated when used
+    pub const fn derived_freeze_v1<T: ConstFreeze + ?Sized>() -> u64 {
+        T::FREEZE_V1
+    }
+
+    /// default `Freezable::FREEZE_V1`, of the manual impls that don't override it
+    pub const fn no_const_freeze_v1() -> u64 {
+        panic!("this type doesn't override Freezable::FREEZE_V1")
+    }
+
+    /// `T::FREEZE_V1`, only implemented for `ConstEval`: `#[derive(Freezable)]` reads
+    /// the freeze of the fields' types through a generic `M: LazyFreeze<T>`,
+    /// so that it is not evaluated before `M = ConstEval` is known
+    pub trait LazyFreeze<T: ?Sized> {
+        const FREEZE_V1: u64;
+    }
+
+    pub enum ConstEval {}
+
+    impl<T: Freezable + ?Sized> LazyFreeze<T> for ConstEval {
+        const FREEZE_V1: u64 = T::FREEZE_V1;
     }
 }
+
+/// A `#[frozone(remote)]` mirror that misses a field of the remote struct
+/// doesn't compile:
+///
+/// ```compile_fail,E0027
+/// mod other {
+///     pub struct Config {
+///         pub name: String,
+///         pub retries: u8,
+///     }
+/// }
+///
+/// #[derive(frozone::Freezable)]
+/// #[frozone(remote = "other::Config")]
+/// struct ConfigMirror {
+///     name: String,
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct RemoteMissingField;
+
+/// Higher-ranked fn pointers aren't `Freezable` (only their `'static`
+/// versions are, e.g `fn(&'static str) -> bool`):
+///
+/// ```compile_fail
+/// #[derive(frozone::Freezable)]
+/// struct Hooks {
+///     on_event: fn(&str) -> bool,
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct HigherRankedFn;
+
+/// Higher-ranked trait objects aren't `Freezable` either (only their
+/// `'static` versions are, e.g `dyn Fn(&'static str)`):
+///
+/// ```compile_fail
+/// #[derive(frozone::Freezable)]
+/// struct Parser {
+///     on_line: Box<dyn Fn(&str)>,
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct HigherRankedDyn;
+
+/// The fields of `#[assume_frozen]` variants have no schema, that
+/// `#[frozone(with)]` or `#[frozone(as)]` could give them:
+///
+/// ```compile_fail
+/// #[derive(frozone::Freezable)]
+/// enum Event {
+///     #[assume_frozen]
+///     Tick(#[frozone(as = u64)] u32),
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct AssumeFrozenVariantField;
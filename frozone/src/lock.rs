@@ -0,0 +1,404 @@
+//! Snapshot testing of frozen types against a lockfile
+//!
+//! Instead of hard-coding the freeze of every type in the tests, the freezes
+//! (and schemas, to explain what changed) are stored in a `frozone.lock` file
+//! committed along with the code:
+//!
+//! ```rust,ignore
+//! #[test]
+//! fn frozen() {
+//!     frozone::assert_locked!("frozone.lock", Frozone, Part, Suit);
+//! }
+//! ```
+//!
+//! The test fails when a type's freeze doesn't match the lockfile (or when
+//! the type is not in it yet). Running the tests with `FROZONE_UPDATE=1`
+//! rewrites the entries of the checked types instead of failing.
+//!
+//! Each entry records the [`Algorithm`] it was frozen with: v1 by default,
+//! or v2 with a [`Profile`] (e.g to pin what serde sees):
+//!
+//! ```rust,ignore
+//! use frozone::lock::Algorithm;
+//! use frozone::schema::Profile;
+//!
+//! frozone::assert_locked!("frozone.lock", with Algorithm::V2(Profile::Serialization), Config);
+//! ```
+use crate::schema::Profile;
+use crate::{Freezable, Schema};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// environment variable that makes [`Lockfile::finish`] rewrite the lockfile
+pub const UPDATE_ENV_VAR: &str = "FROZONE_UPDATE";
+
+const HEADER: &str = "\
+# frozone lockfile: the freeze and schema of each pinned type
+# regenerate the entries with `FROZONE_UPDATE=1 cargo test`
+";
+
+/// Algorithm a lockfile entry is frozen with, written as its `algorithm = ..` line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    /// [`Freezable::freeze`]
+    #[default]
+    V1,
+    /// [`Freezable::freeze_with`] (v2 is not finalized yet, see [`crate::hash::v2`])
+    V2(Profile),
+}
+
+impl Algorithm {
+    fn freeze(self, schema: &Schema) -> u64 {
+        match self {
+            Algorithm::V1 => schema.freeze_v1(),
+            Algorithm::V2(profile) => schema.freeze_with(profile),
+        }
+    }
+}
+
+impl core::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(match self {
+            Algorithm::V1 => "v1",
+            Algorithm::V2(Profile::Strict) => "v2",
+            Algorithm::V2(Profile::Serialization) => "v2 serialization",
+            Algorithm::V2(Profile::Layout) => "v2 layout",
+        })
+    }
+}
+
+impl core::str::FromStr for Algorithm {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "v1" => Ok(Algorithm::V1),
+            "v2" => Ok(Algorithm::V2(Profile::Strict)),
+            "v2 serialization" => Ok(Algorithm::V2(Profile::Serialization)),
+            "v2 layout" => Ok(Algorithm::V2(Profile::Layout)),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    algorithm: Algorithm,
+    freeze: u64,
+    /// `None` if the schema stored in the lockfile can't be parsed
+    /// (e.g written by another version of frozone)
+    schema: Option<Schema>,
+}
+
+/// Freezes of the types pinned in a lockfile, see the [module](self) documentation
+///
+/// Entries of types that are not checked are left untouched, so that
+/// several tests can pin types in the same lockfile: the updates of the
+/// tests running in parallel (in the same process) are serialized
+#[derive(Debug)]
+pub struct Lockfile {
+    path: PathBuf,
+    entries: BTreeMap<String, Entry>,
+    /// checked type names, along with their current entry
+    checked: Vec<(String, Entry)>,
+    update: bool,
+}
+
+impl Lockfile {
+    /// Reads the lockfile at `path` (relative paths are relative to the
+    /// crate's root when running `cargo test`), an absent file has no entries
+    ///
+    /// # Panics
+    /// if the lockfile can't be read or is malformed
+    pub fn open(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let entries = read(&path);
+        let update = std::env::var(UPDATE_ENV_VAR).is_ok_and(|v| !v.is_empty() && v != "0");
+        Lockfile {
+            path,
+            entries,
+            checked: Vec::new(),
+            update,
+        }
+    }
+
+    /// Whether [`Lockfile::finish`] rewrites the lockfile instead of failing,
+    /// defaults to `FROZONE_UPDATE` being set (to anything but `0`)
+    pub fn update(&mut self, update: bool) -> &mut Self {
+        self.update = update;
+        self
+    }
+
+    /// Checks the freeze of `T` against the lockfile entry `name` (errors are
+    /// only reported by [`Lockfile::finish`])
+    pub fn check<T: Freezable>(&mut self, name: &str) -> &mut Self {
+        self.check_with::<T>(name, Algorithm::V1)
+    }
+
+    /// [`Lockfile::check`], freezing `T` with `algorithm` (an entry locked
+    /// with another algorithm doesn't match)
+    pub fn check_with<T: Freezable>(&mut self, name: &str, algorithm: Algorithm) -> &mut Self {
+        let schema = T::schema();
+        let entry = Entry {
+            algorithm,
+            freeze: algorithm.freeze(&schema),
+            schema: Some(schema),
+        };
+        self.checked.push((name.to_string(), entry));
+        self
+    }
+
+    /// Descriptions of the entries checked for several types (that can't
+    /// be locked together)
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut names: Vec<&str> = Vec::new();
+        for (i, (name, entry)) in self.checked.iter().enumerate() {
+            let conflicting = self.checked[..i]
+                .iter()
+                .any(|(other, other_entry)| other == name && other_entry != entry);
+            if conflicting && !names.contains(&&**name) {
+                names.push(name);
+            }
+        }
+        names
+            .into_iter()
+            .map(|name| {
+                format!(
+                    "{} is checked for different types (give them distinct entry names)",
+                    name
+                )
+            })
+            .collect()
+    }
+
+    /// Descriptions of the checked types whose freeze doesn't match the
+    /// lockfile, along with the [`Lockfile::conflicts`]
+    pub fn mismatches(&self) -> Vec<String> {
+        let mismatches =
+            self.checked
+                .iter()
+                .filter_map(|(name, current)| match self.entries.get(name) {
+                    None => Some(format!("{} is not in the lockfile", name)),
+                    Some(locked) if locked.algorithm != current.algorithm => Some(format!(
+                        "{} is locked with the algorithm {}, not {}",
+                        name, locked.algorithm, current.algorithm
+                    )),
+                    Some(locked) if locked.freeze == current.freeze => None,
+                    Some(locked) => {
+                        let mut msg = format!(
+                            "{} changed (freeze {} -> {})",
+                            name, locked.freeze, current.freeze
+                        );
+                        if let (Some(old), Some(new)) = (&locked.schema, &current.schema) {
+                            for change in old.diff(new) {
+                                let _ = write!(msg, "\n    {}", change);
+                            }
+                        }
+                        Some(msg)
+                    }
+                });
+        self.conflicts().into_iter().chain(mismatches).collect()
+    }
+
+    /// Rewrites the lockfile in update mode, else fails if any checked
+    /// type doesn't match its entry
+    ///
+    /// # Panics
+    /// if a type doesn't match the lockfile (outside of update mode), an
+    /// entry is checked for several types or the lockfile can't be written
+    pub fn finish(&mut self) {
+        let conflicts = self.conflicts();
+        if self.update && !conflicts.is_empty() {
+            panic!(
+                "can't update the lockfile {}:\n  {}",
+                self.path.display(),
+                conflicts.join("\n  "),
+            );
+        }
+        if !self.update {
+            let mismatches = self.mismatches();
+            if !mismatches.is_empty() {
+                panic!(
+                    "frozen types don't match the lockfile {}:\n  {}\n(run with {}=1 to update it)",
+                    self.path.display(),
+                    mismatches.join("\n  "),
+                    UPDATE_ENV_VAR,
+                );
+            }
+            return;
+        }
+        // the lockfile is read again, as another test may have updated it
+        // since it was opened
+        let _guard = UPDATES.lock().unwrap_or_else(|e| e.into_inner());
+        let locked = read(&self.path);
+        let mut entries = locked.clone();
+        entries.extend(self.checked.drain(..));
+        if entries != locked || !self.path.exists() {
+            std::fs::write(&self.path, print(&entries)).unwrap_or_else(|e| {
+                panic!("could not write lockfile {}: {}", self.path.display(), e)
+            });
+        }
+        self.entries = entries;
+    }
+}
+
+/// held while a lockfile is read, merged and written back by
+/// [`Lockfile::finish`], so that concurrent updates don't drop each other's
+/// entries (tests sharing a lockfile run in parallel)
+static UPDATES: Mutex<()> = Mutex::new(());
+
+/// entries of the lockfile at `path`, an absent file having none
+///
+/// # Panics
+/// if the lockfile can't be read or is malformed
+fn read(path: &Path) -> BTreeMap<String, Entry> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => parse(&text).unwrap_or_else(|(line, message)| {
+            panic!(
+                "malformed lockfile {}:{}: {}",
+                path.display(),
+                line,
+                message
+            )
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(e) => panic!("could not read lockfile {}: {}", path.display(), e),
+    }
+}
+
+/// Name of the lockfile entry of `T` in [`assert_locked!`], rendered from its
+/// schema (so that it doesn't depend on how the type is spelled): the name of
+/// a derived type (without its path nor generic arguments), and e.g `Vec<u8>`,
+/// `[u8; 4]` or `(u8, u16)` for the other types
+///
+/// The instantiations of a derived generic type share an entry name, and have
+/// to be given explicit ones
+pub fn entry_name<T: Freezable + ?Sized>() -> String {
+    let mut name = String::new();
+    write_entry_name(&mut name, &T::schema());
+    name
+}
+
+fn write_entry_name(out: &mut String, schema: &Schema) {
+    let list = |out: &mut String, schemas: &[Schema]| {
+        for (i, schema) in schemas.iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            write_entry_name(out, schema);
+        }
+    };
+    match schema {
+        Schema::Opaque(name)
+        | Schema::Struct { name, .. }
+        | Schema::Enum { name, .. }
+        | Schema::Recursion { name, .. } => out.push_str(name),
+        Schema::Container { name, args } => {
+            out.push_str(name);
+            out.push('<');
+            list(out, args);
+            out.push('>');
+        }
+        Schema::Array { len, item } => {
+            out.push('[');
+            write_entry_name(out, item);
+            let _ = write!(out, "; {}]", len);
+        }
+        Schema::Tuple(items) => {
+            out.push('(');
+            list(out, items);
+            if items.len() == 1 {
+                out.push(',');
+            }
+            out.push(')');
+        }
+        Schema::AssumeFrozen | Schema::FrozenGenerics(_) => out.push('_'),
+    }
+}
+
+/// Checks the freeze of each type against the lockfile at `path`, the
+/// entries being named after the types' schemas (see [`entry_name`]), e.g
+/// `assert_locked!("frozone.lock", A, Vec<u8>)`, or given explicit names
+/// (`assert_locked!("frozone.lock", "A<u8>" => A<u8>, "A<u16>" => A<u16>)`)
+///
+/// The types are frozen with v1, unless an [`Algorithm`] is given
+/// (`assert_locked!("frozone.lock", with Algorithm::V2(Profile::Strict), A)`),
+/// see [`Lockfile`]
+#[macro_export]
+macro_rules! assert_locked {
+    ($path:expr, with $algorithm:expr, $($name:literal => $t:ty),+ $(,)?) => {{
+        let mut lockfile = $crate::lock::Lockfile::open($path);
+        $(lockfile.check_with::<$t>($name, $algorithm);)+
+        lockfile.finish();
+    }};
+    ($path:expr, with $algorithm:expr, $($t:ty),+ $(,)?) => {{
+        let mut lockfile = $crate::lock::Lockfile::open($path);
+        $(lockfile.check_with::<$t>(&$crate::lock::entry_name::<$t>(), $algorithm);)+
+        lockfile.finish();
+    }};
+    ($path:expr, $($name:literal => $t:ty),+ $(,)?) => {
+        $crate::assert_locked!($path, with $crate::lock::Algorithm::V1, $($name => $t),+)
+    };
+    ($path:expr, $($t:ty),+ $(,)?) => {
+        $crate::assert_locked!($path, with $crate::lock::Algorithm::V1, $($t),+)
+    };
+}
+
+/// each entry is a `[name]` line, then an `algorithm = ..` line and a
+/// `freeze = N` line, then the (multi-line) text form of the schema
+fn print(entries: &BTreeMap<String, Entry>) -> String {
+    let mut out = String::from(HEADER);
+    for (name, entry) in entries {
+        let _ = write!(
+            out,
+            "\n[{}]\nalgorithm = {}\nfreeze = {}\n",
+            name, entry.algorithm, entry.freeze
+        );
+        if let Some(schema) = &entry.schema {
+            let _ = writeln!(out, "{}", schema);
+        }
+    }
+    out
+}
+
+fn parse(text: &str) -> Result<BTreeMap<String, Entry>, (usize, &'static str)> {
+    let mut entries = BTreeMap::new();
+    let mut lines = text.lines().enumerate().peekable();
+    while let Some((i, line)) = lines.next() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let name = line
+            .strip_prefix('[')
+            .and_then(|l| l.strip_suffix(']'))
+            .ok_or((i + 1, "expected a `[name]` line"))?;
+        let algorithm = lines
+            .next()
+            .and_then(|(_, l)| l.strip_prefix("algorithm = "))
+            .ok_or((i + 2, "expected an `algorithm = ..` line"))?
+            .parse()
+            .map_err(|()| (i + 2, "unknown algorithm"))?;
+        let freeze = lines
+            .next()
+            .and_then(|(_, l)| l.strip_prefix("freeze = "))
+            .and_then(|f| f.parse().ok())
+            .ok_or((i + 3, "expected a `freeze = N` line"))?;
+        // the schema spans all the lines up to the next entry
+        let mut schema = String::new();
+        while let Some((_, l)) = lines.next_if(|(_, l)| !l.starts_with('[')) {
+            schema.push_str(l);
+            schema.push('\n');
+        }
+        let entry = Entry {
+            algorithm,
+            freeze,
+            schema: schema.parse().ok(),
+        };
+        if entries.insert(name.to_string(), entry).is_some() {
+            return Err((i + 1, "duplicate entry"));
+        }
+    }
+    Ok(entries)
+}
@@ -0,0 +1,686 @@
+//! Structural description of a frozen type
+//!
+//! A [`Schema`] is the tree frozone walks when freezing a type: structs and
+//! enums with their field/variant names, containers, opaque leaves and
+//! recursion back-edges. [`Freezable::freeze`](crate::Freezable::freeze) is
+//! computed from it, so a schema and its freeze can never disagree.
+mod diff;
+mod profile;
+mod text;
+mod visit;
+
+pub use diff::{Change, ChangeKind};
+pub use profile::Profile;
+pub use text::ParseError;
+pub use visit::{Printer, Visitor};
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::hash::{v1, v2};
+
+/// Node of the structural description of a type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schema {
+    /// type frozen by its name only (primitives, `String`, `std::fs::File`, ...)
+    Opaque(Cow<'static, str>),
+    /// generic container such as `Vec<T>`, `Option<T>` or `BTreeMap<K, V>`
+    /// (`args` may be empty when the generic arguments are not frozen, e.g `Range<T>`)
+    Container {
+        name: Cow<'static, str>,
+        args: Vec<Schema>,
+    },
+    /// `[T; N]`
+    Array { len: usize, item: Box<Schema> },
+    /// `(A, B, ...)`
+    Tuple(Vec<Schema>),
+    /// `#[derive(Freezable)]` struct
+    Struct {
+        name: Cow<'static, str>,
+        shape: Shape,
+        fields: Vec<Field>,
+        serde: Representation,
+        /// `#[frozone(positional)]`: the order of the fields is frozen (by v2)
+        positional: bool,
+        /// memory layout of `#[repr(...)]` structs
+        layout: Option<Layout>,
+    },
+    /// `#[derive(Freezable)]` enum
+    Enum {
+        name: Cow<'static, str>,
+        variants: Vec<Variant>,
+        serde: Representation,
+        /// `#[frozone(positional)]`: the order of the variants is frozen (by v2)
+        positional: bool,
+        /// memory layout of `#[repr(...)]` enums
+        layout: Option<Layout>,
+    },
+    /// back-edge to a type already being frozen higher in the type graph,
+    /// `depth` being the depth it was first found at
+    Recursion { name: Cow<'static, str>, depth: u32 },
+    /// `#[assume_frozen]` field: nothing but its name is frozen
+    AssumeFrozen,
+    /// `#[assume_frozen(freeze_generics)]` field: only the generic arguments
+    /// of each segment of the type's path are frozen
+    FrozenGenerics(Vec<GenericArgs>),
+}
+
+/// Struct or variant field (unnamed fields are named after their position)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: Cow<'static, str>,
+    pub schema: Schema,
+    pub serde: Serde,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub name: Cow<'static, str>,
+    /// explicit discriminant, e.g `A = 1`
+    pub discriminant: Option<Discriminant>,
+    pub shape: Shape,
+    /// empty for `#[assume_frozen]` variants, whose fields are not frozen
+    pub fields: Vec<Field>,
+    pub assume_frozen: bool,
+    pub serde: Serde,
+}
+
+/// How a field or variant is (de)serialized by serde, from its `#[serde(...)]`
+/// attributes (and the ones of the type it is in, such as `rename_all`)
+///
+/// Only [`Schema::freeze_v2`] freezes it: [v1](crate::hash::v1) freezes the Rust names
+/// (a skipped field still being frozen by v1, as an [`Schema::AssumeFrozen`] one)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Serde {
+    /// name when serialized, if not the Rust one (`rename`, `rename_all`)
+    pub serialize_name: Option<Cow<'static, str>>,
+    /// name when deserialized, if not the Rust one
+    pub deserialize_name: Option<Cow<'static, str>>,
+    /// other names accepted when deserializing (`alias`), sorted
+    pub aliases: Vec<Cow<'static, str>>,
+    pub serialized: Serialized,
+    pub deserialized: Deserialized,
+    /// `flatten`: the fields of the field are (de)serialized as if they
+    /// were fields of the struct it is in
+    pub flatten: bool,
+}
+
+/// Whether a field or variant is serialized
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Serialized {
+    #[default]
+    Always,
+    /// `skip_serializing_if`: the field may be missing from the serialized form
+    Conditionally,
+    /// `skip`, `skip_serializing`
+    Never,
+}
+
+/// Whether a field or variant is deserialized, and what if it is missing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Deserialized {
+    /// a missing field is an error
+    #[default]
+    Required,
+    /// `default` (on the field or its struct): a missing field is defaulted
+    Defaulted,
+    /// `skip`, `skip_deserializing`: the field is always defaulted
+    Never,
+}
+
+/// How a struct or enum is (de)serialized by serde, from its `#[serde(...)]`
+/// attributes
+///
+/// Only [`Schema::freeze_v2`] freezes it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Representation {
+    /// serde's default: enums are externally tagged (`{"Variant": ...}`)
+    #[default]
+    Default,
+    /// `transparent`: (de)serialized as its only (non-skipped) field
+    Transparent,
+    /// `tag = "t"`: the variant name is in the `t` field of the variant's
+    /// content (`{"t": "Variant", ...}`), or a `t` field is added to a struct
+    Internal { tag: Cow<'static, str> },
+    /// `tag = "t", content = "c"`: `{"t": "Variant", "c": ...}`
+    Adjacent {
+        tag: Cow<'static, str>,
+        content: Cow<'static, str>,
+    },
+    /// `untagged`: only the variant's content, without its name
+    Untagged,
+}
+
+/// Memory layout of a `#[repr(...)]` struct or enum, as compiled for the
+/// current target
+///
+/// Only frozen under [`Profile::Layout`], as it depends on the target
+/// (e.g the size of `usize` and pointers)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    /// the `#[repr(...)]` items, e.g `"C"`, `"u8"` or `"C, align(8)"`
+    pub repr: Cow<'static, str>,
+    /// `size_of`, in bytes
+    pub size: usize,
+    /// `align_of`, in bytes
+    pub align: usize,
+    /// `offset_of` each field, in their declaration order (empty for enums,
+    /// the offsets of variant fields not being available)
+    pub offsets: Vec<usize>,
+}
+
+/// How the fields of a struct or variant are declared (frozen by v2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    /// `struct A;`
+    Unit,
+    /// `struct A(u8);`
+    Tuple,
+    /// `struct A { a: u8 }`
+    Named,
+}
+
+/// Value of an explicit enum discriminant, along with the size
+/// (in bytes) of the integer type it was written as (8 for `isize`
+/// and `usize`, whatever the target)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Discriminant {
+    pub value: i128,
+    pub size: u8,
+}
+
+/// Generic arguments of a path segment of an `#[assume_frozen(freeze_generics)]` type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenericArgs {
+    /// `std`, `vec` in `std::vec::Vec<T>`
+    None,
+    /// `<T>` in `std::vec::Vec<T>`, `<Item = T>` in `dyn Iterator<Item = T>`
+    AngleBracketed {
+        args: Vec<Schema>,
+        /// associated type bindings, e.g `("Item", T)`
+        bindings: Vec<(Cow<'static, str>, Schema)>,
+    },
+    /// `(A, B) -> C` in `Fn(A, B) -> C`
+    Parenthesized {
+        inputs: Vec<Schema>,
+        output: Box<Schema>,
+    },
+}
+
+impl Schema {
+    /// Computes the freeze of the described type, with the default
+    /// algorithm: [`Schema::freeze_v1`]
+    pub fn freeze(&self) -> u64 {
+        self.freeze_v1()
+    }
+
+    /// Computes the freeze of the described type with the algorithm v1,
+    /// see [`crate::hash::v1`]
+    pub fn freeze_v1(&self) -> u64 {
+        self.freeze_v1_by(&mut Schema::freeze_v1)
+    }
+
+    /// Freeze v1 of the node, the freezes of the types it contains (the
+    /// arguments of containers, the items of arrays and tuples, and the fields
+    /// of structs and enum variants, in this order) being given by `freeze`
+    fn freeze_v1_by(&self, freeze: &mut dyn FnMut(&Schema) -> u64) -> u64 {
+        let mut freezes = |schemas: &[Schema]| schemas.iter().map(&mut *freeze).collect::<Vec<_>>();
+        match self {
+            Schema::Opaque(name) => v1::opaque(name),
+            Schema::Container { name, args } => v1::container(name, &freezes(args)),
+            Schema::Array { len, item } => v1::array(*len, freeze(item)),
+            Schema::Tuple(items) => v1::tuple(&freezes(items)),
+            Schema::Struct { shape, fields, .. } => {
+                let fields: Vec<_> = fields
+                    .iter()
+                    .map(|f| {
+                        // unnamed fields are all hashed as `_`
+                        let name = match shape {
+                            Shape::Tuple => "_",
+                            _ => &f.name,
+                        };
+                        (name, freeze(&f.schema))
+                    })
+                    .collect();
+                v1::sum_named(&fields)
+            }
+            Schema::Enum { variants, .. } => {
+                let variants: Vec<_> = variants
+                    .iter()
+                    .map(|v| (&*v.name, v.freeze_by(freeze)))
+                    .collect();
+                v1::sum_named(&variants)
+            }
+            Schema::Recursion { depth, .. } => v1::recursion(*depth),
+            Schema::AssumeFrozen => v1::ASSUME_FROZEN,
+            Schema::FrozenGenerics(segments) => {
+                let segments: Vec<_> = segments.iter().map(GenericArgs::freeze).collect();
+                v1::frozen_generics(&segments)
+            }
+        }
+    }
+
+    /// Computes the freeze of the described type with the algorithm v2
+    /// (not finalized yet), see [`crate::hash::v2`]
+    pub fn freeze_v2(&self) -> u64 {
+        self.freeze_v2_at(0, false)
+    }
+
+    /// Computes the freeze of the described type with the algorithm v2,
+    /// the types equivalent under `profile` having the same freeze (see
+    /// [`Schema::with_profile`]), `freeze_with(Profile::Strict)` being `freeze_v2()`
+    pub fn freeze_with(&self, profile: Profile) -> u64 {
+        match profile {
+            Profile::Strict => self.freeze_v2(),
+            // (the layout profile freezes every type as positional)
+            Profile::Layout => self.freeze_v2_at(0, true),
+            Profile::Serialization => self.with_profile(profile).freeze_v2(),
+        }
+    }
+
+    /// `depth`: number of structs and enums above the node,
+    /// `layout`: whether the [`Layout`]s (and the order of all the fields
+    /// and variants) are frozen
+    fn freeze_v2_at(&self, depth: u32, layout: bool) -> u64 {
+        self.freeze_v2_by(depth, layout, &mut |s, depth| s.freeze_v2_at(depth, layout))
+    }
+
+    /// Freeze v2 of the node at `depth`, the freezes of the types it contains
+    /// (in the order of [`Schema::freeze_v1_by`]) being given by `freeze`,
+    /// along with their depth
+    fn freeze_v2_by(
+        &self,
+        depth: u32,
+        layout: bool,
+        freeze: &mut dyn FnMut(&Schema, u32) -> u64,
+    ) -> u64 {
+        let mut freezes = |schemas: &[Schema], depth| {
+            schemas.iter().map(|s| freeze(s, depth)).collect::<Vec<_>>()
+        };
+        match self {
+            Schema::Opaque(name) => v2::opaque(name),
+            Schema::Container { name, args } => v2::container(name, &freezes(args, depth)),
+            Schema::Array { len, item } => v2::array(*len, freeze(item, depth)),
+            Schema::Tuple(items) => v2::tuple(&freezes(items, depth)),
+            Schema::Struct {
+                shape,
+                fields,
+                serde,
+                positional,
+                layout: struct_layout,
+                ..
+            } => {
+                let named: Vec<_> = fields
+                    .iter()
+                    .filter_map(|f| {
+                        // unnamed fields are all hashed as `_`, the name
+                        // of the field of a transparent struct isn't serialized
+                        let name = match (shape, serde) {
+                            (_, Representation::Transparent) => "",
+                            (Shape::Tuple, _) => "_",
+                            _ => &f.name,
+                        };
+                        f.serde.named_v2(name, freeze(&f.schema, depth + 1))
+                    })
+                    .collect();
+                // unnamed fields are identified by their position in every
+                // format, whether the struct is positional or not
+                let ordered = *positional
+                    || layout
+                    || (*shape == Shape::Tuple && *serde != Representation::Transparent);
+                let freeze = fields_v2(named, ordered);
+                let freeze = match serde {
+                    // (de)serialized as its field, whatever its shape
+                    Representation::Transparent => freeze,
+                    _ => shape.freeze_v2(freeze),
+                };
+                let freeze = serde.freeze_v2(freeze);
+                match struct_layout {
+                    Some(l) if layout => l.freeze_v2(fields, freeze),
+                    _ => freeze,
+                }
+            }
+            Schema::Enum {
+                variants,
+                serde,
+                positional,
+                layout: enum_layout,
+                ..
+            } => {
+                let variants: Vec<_> = (0..)
+                    .zip(variants)
+                    .filter_map(|(i, v)| {
+                        let fields: Vec<_> = match v.shape {
+                            // named fields are frozen as the ones of a struct
+                            Shape::Named if !v.fields.is_empty() => {
+                                let named: Vec<_> = v
+                                    .fields
+                                    .iter()
+                                    .filter_map(|f| {
+                                        let freeze = freeze(&f.schema, depth + 1);
+                                        f.serde.named_v2(&f.name, freeze)
+                                    })
+                                    .collect();
+                                Vec::from([fields_v2(named, *positional || layout)])
+                            }
+                            _ => v
+                                .fields
+                                .iter()
+                                .filter_map(|f| f.serde.content_v2(freeze(&f.schema, depth + 1)))
+                                .collect(),
+                        };
+                        let content = v2::variant(v.discriminant, v.assume_frozen, &fields);
+                        let content = v.shape.freeze_v2(content);
+                        let (name, content) = v.serde.named_v2(&v.name, content)?;
+                        Some((i, name, content))
+                    })
+                    .collect();
+                let freeze = match *positional || layout {
+                    // the position of a variant is its index in the declaration
+                    true => v2::positional(&variants),
+                    false => {
+                        let variants: Vec<_> = variants.iter().map(|(_, n, v)| (*n, *v)).collect();
+                        v2::sum_named(&variants)
+                    }
+                };
+                let freeze = serde.freeze_v2(freeze);
+                match enum_layout {
+                    Some(l) if layout => l.freeze_v2(&[], freeze),
+                    _ => freeze,
+                }
+            }
+            // the back-edge is in the struct or enum at `depth`,
+            // and points to the one at `target`
+            Schema::Recursion { depth: target, .. } => {
+                v2::recursion(depth.saturating_sub(*target) + 1)
+            }
+            Schema::AssumeFrozen => v2::ASSUME_FROZEN,
+            // (whose generic arguments aren't walked as contained types)
+            Schema::FrozenGenerics(segments) => {
+                let freezes = |schemas: &[Schema]| {
+                    schemas
+                        .iter()
+                        .map(|s| s.freeze_v2_at(depth, layout))
+                        .collect::<Vec<_>>()
+                };
+                let segments: Vec<_> = segments
+                    .iter()
+                    .map(|segment| match segment {
+                        GenericArgs::None => v2::no_generic_args(),
+                        GenericArgs::AngleBracketed { args, bindings } => {
+                            let mut args = freezes(args);
+                            args.extend(bindings.iter().map(|(name, schema)| {
+                                v2::named(name, schema.freeze_v2_at(depth, layout))
+                            }));
+                            v2::angle_bracketed_args(&args)
+                        }
+                        GenericArgs::Parenthesized { inputs, output } => v2::parenthesized_args(
+                            &freezes(inputs),
+                            output.freeze_v2_at(depth, layout),
+                        ),
+                    })
+                    .collect();
+                v2::frozen_generics(&segments)
+            }
+        }
+    }
+}
+
+/// Freezes of a schema, along with the ones of the types it contains (in
+/// the order of [`Schema::freeze_v1_by`]), computed once, bottom-up, for the
+/// walks that need the freeze of every sub-structure
+struct Freezes {
+    v1: u64,
+    v2: u64,
+    /// under [`Profile::Layout`]
+    layout: u64,
+    inner: Vec<Freezes>,
+}
+
+impl Freezes {
+    fn of(schema: &Schema) -> Self {
+        Freezes::at(schema, 0)
+    }
+
+    /// `depth`: number of structs and enums above the schema
+    fn at(schema: &Schema, depth: u32) -> Self {
+        let mut inner = Vec::new();
+        let v2 = schema.freeze_v2_by(depth, false, &mut |s, depth| {
+            let freezes = Freezes::at(s, depth);
+            let v2 = freezes.v2;
+            inner.push(freezes);
+            v2
+        });
+        let mut contained = inner.iter();
+        let v1 = schema.freeze_v1_by(&mut |_| contained.next().map_or(0, |f| f.v1));
+        let mut contained = inner.iter();
+        let layout = schema.freeze_v2_by(depth, true, &mut |_, _| {
+            contained.next().map_or(0, |f| f.layout)
+        });
+        Freezes {
+            v1,
+            v2,
+            layout,
+            inner,
+        }
+    }
+
+    /// whether the two schemas have the same freezes, with all algorithms
+    /// and profiles that freeze different things
+    fn same(&self, other: &Freezes) -> bool {
+        self.v1 == other.v1 && self.v2 == other.v2 && self.layout == other.layout
+    }
+
+    /// freezes of the fields of each of the `variants` of the enum
+    fn of_variants(&self, variants: &[Variant]) -> Vec<&[Freezes]> {
+        let mut inner = &self.inner[..];
+        variants
+            .iter()
+            .map(|v| {
+                let (fields, rest) = inner.split_at(v.fields.len().min(inner.len()));
+                inner = rest;
+                fields
+            })
+            .collect()
+    }
+}
+
+impl Schema {
+    /// Marks every struct and enum of the schema as `#[frozone(positional)]`,
+    /// so that the order of their fields and variants is frozen (by v2), as
+    /// needed by non self-describing formats (bincode, postcard...)
+    pub fn positional(&self) -> Schema {
+        let mut schema = self.clone();
+        schema.set_positional();
+        schema
+    }
+
+    fn set_positional(&mut self) {
+        let fields = |fields: &mut Vec<Field>| {
+            fields.iter_mut().for_each(|f| f.schema.set_positional());
+        };
+        match self {
+            Schema::Container { args, .. } => args.iter_mut().for_each(Schema::set_positional),
+            Schema::Array { item, .. } => item.set_positional(),
+            Schema::Tuple(items) => items.iter_mut().for_each(Schema::set_positional),
+            Schema::Struct {
+                fields: f,
+                positional,
+                ..
+            } => {
+                *positional = true;
+                fields(f);
+            }
+            Schema::Enum {
+                variants,
+                positional,
+                ..
+            } => {
+                *positional = true;
+                variants.iter_mut().for_each(|v| fields(&mut v.fields));
+            }
+            Schema::FrozenGenerics(segments) => {
+                for segment in segments {
+                    match segment {
+                        GenericArgs::None => {}
+                        GenericArgs::AngleBracketed { args, bindings } => {
+                            args.iter_mut().for_each(Schema::set_positional);
+                            bindings.iter_mut().for_each(|(_, s)| s.set_positional());
+                        }
+                        GenericArgs::Parenthesized { inputs, output } => {
+                            inputs.iter_mut().for_each(Schema::set_positional);
+                            output.set_positional();
+                        }
+                    }
+                }
+            }
+            Schema::Opaque(_) | Schema::Recursion { .. } | Schema::AssumeFrozen => {}
+        }
+    }
+}
+
+/// freeze in v2 of the (name, freeze) pairs of the named (or, in structs,
+/// unnamed) fields of a struct or variant
+fn fields_v2(named: Vec<(&str, u64)>, positional: bool) -> u64 {
+    match positional {
+        // skipped fields aren't serialized, so don't take a position
+        true => {
+            let named: Vec<_> = (0..).zip(named).map(|(i, (n, f))| (i, n, f)).collect();
+            v2::positional(&named)
+        }
+        false => v2::sum_named(&named),
+    }
+}
+
+impl Serde {
+    /// Whether the field or variant is neither serialized nor deserialized
+    /// (`#[serde(skip)]`), in which case v2 doesn't freeze it
+    pub fn is_skipped(&self) -> bool {
+        self.serialized == Serialized::Never && self.deserialized == Deserialized::Never
+    }
+
+    /// freeze of the field or variant in v2, along with its presence
+    /// (`None` when skipped)
+    fn content_v2(&self, freeze: u64) -> Option<u64> {
+        if self.is_skipped() {
+            return None;
+        }
+        if self.serialized == Serialized::Always && self.deserialized == Deserialized::Required {
+            return Some(freeze);
+        }
+        Some(v2::presence(
+            self.serialized as u8,
+            self.deserialized as u8,
+            freeze,
+        ))
+    }
+
+    /// (name, freeze) pair of the field or variant `name` in v2: its
+    /// serialized name, along with its deserialized names if they differ
+    /// (`None` when skipped)
+    fn named_v2<'a>(&'a self, name: &'a str, freeze: u64) -> Option<(&'a str, u64)> {
+        let content = self.content_v2(freeze)?;
+        if self.flatten {
+            // the name of a flattened field isn't serialized
+            return Some(("", v2::flattened(content)));
+        }
+        let serialize = self.serialize_name.as_deref().unwrap_or(name);
+        let deserialize = self.deserialize_name.as_deref().unwrap_or(name);
+        if serialize == deserialize && self.aliases.is_empty() {
+            return Some((serialize, content));
+        }
+        let aliases: Vec<_> = self.aliases.iter().map(|a| &**a).collect();
+        Some((
+            serialize,
+            v2::deserialized_as(deserialize, &aliases, content),
+        ))
+    }
+}
+
+impl Representation {
+    /// freeze in v2 of a struct or enum whose fields or variants freeze to `freeze`
+    fn freeze_v2(&self, freeze: u64) -> u64 {
+        match self {
+            Representation::Default => freeze,
+            Representation::Transparent => v2::representation(1, "", "", freeze),
+            Representation::Internal { tag } => v2::representation(2, tag, "", freeze),
+            Representation::Adjacent { tag, content } => {
+                v2::representation(3, tag, content, freeze)
+            }
+            Representation::Untagged => v2::representation(4, "", "", freeze),
+        }
+    }
+}
+
+impl Shape {
+    /// freeze in v2 of a struct or variant whose fields freeze to `freeze`
+    fn freeze_v2(&self, freeze: u64) -> u64 {
+        match self {
+            Shape::Unit => v2::shaped(0, freeze),
+            Shape::Tuple => v2::shaped(1, freeze),
+            Shape::Named => freeze,
+        }
+    }
+}
+
+impl Layout {
+    /// freeze in v2 (under [`Profile::Layout`]) of a struct (with `fields`)
+    /// or enum whose freeze is `freeze`
+    fn freeze_v2(&self, fields: &[Field], freeze: u64) -> u64 {
+        // each offset is bound to the Rust name of its field
+        let offsets: Vec<_> = fields
+            .iter()
+            .zip(&self.offsets)
+            .map(|(f, offset)| (&*f.name, *offset as u64))
+            .collect();
+        v2::layout(
+            &self.repr,
+            self.size as u64,
+            self.align as u64,
+            &offsets,
+            freeze,
+        )
+    }
+}
+
+impl Variant {
+    /// Computes the freeze (v1) of the variant's content (discriminant and fields)
+    pub fn freeze(&self) -> u64 {
+        self.freeze_by(&mut Schema::freeze_v1)
+    }
+
+    /// [`Variant::freeze`], from the [`Freezes`] of its fields
+    fn cached_freeze(&self, fields: &[Freezes]) -> u64 {
+        let mut fields = fields.iter();
+        self.freeze_by(&mut |_| fields.next().map_or(0, |f| f.v1))
+    }
+
+    /// [`Variant::freeze`], the freezes of the fields being given by `freeze`
+    fn freeze_by(&self, freeze: &mut dyn FnMut(&Schema) -> u64) -> u64 {
+        let fields: Vec<_> = self.fields.iter().map(|f| freeze(&f.schema)).collect();
+        v1::variant(self.discriminant, self.assume_frozen, &fields)
+    }
+}
+
+impl GenericArgs {
+    /// Computes the freeze (v1) of the generic arguments of a path segment
+    pub fn freeze(&self) -> u64 {
+        match self {
+            GenericArgs::None => v1::no_generic_args(),
+            GenericArgs::AngleBracketed { args, bindings } => {
+                let mut args: Vec<_> = args.iter().map(Schema::freeze_v1).collect();
+                args.extend(
+                    bindings
+                        .iter()
+                        .map(|(name, schema)| v1::named(name, schema.freeze_v1())),
+                );
+                v1::angle_bracketed_args(&args)
+            }
+            GenericArgs::Parenthesized { inputs, output } => {
+                let inputs: Vec<_> = inputs.iter().map(Schema::freeze_v1).collect();
+                v1::parenthesized_args(&inputs, output.freeze_v1())
+            }
+        }
+    }
+}
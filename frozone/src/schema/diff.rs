@@ -0,0 +1,549 @@
+//! Path-level differences between two [`Schema`]s
+use super::{
+    Deserialized, Field, Freezes, Layout, Representation, Schema, Serde, Serialized, Shape, Variant,
+};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A difference between two schemas, located by its path from the root
+/// type (e.g `Frozone.power.level`, `Part::Skates`)
+///
+/// Path segments are `.field` for struct fields, `::Variant` for enum
+/// variants (and `::Variant.field` for their fields), `.N` for tuple items
+/// and `<N>` for the N-th argument of multi-arguments containers, other
+/// containers such as `Vec<T>` or `Option<T>` are transparent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub path: String,
+    pub kind: ChangeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// the type at the path changed, e.g `u32 -> u64`
+    Changed { old: String, new: String },
+    /// field or variant only present in the new schema
+    Added,
+    /// field or variant only present in the old schema
+    Removed,
+    /// field or variant removed, while a new one with the exact same
+    /// freeze was added: most likely a rename
+    Renamed { new: String },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ChangeKind::Changed { old, new } => write!(f, "{}: {} -> {}", self.path, old, new),
+            ChangeKind::Added => write!(f, "{} added", self.path),
+            ChangeKind::Removed => write!(f, "{} removed", self.path),
+            ChangeKind::Renamed { new } => write!(f, "{} renamed to {}?", self.path, new),
+        }
+    }
+}
+
+/// short, rust-like description of a schema (e.g `Vec<Part>`)
+struct Summary<'a>(&'a Schema);
+
+impl fmt::Display for Summary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |f: &mut fmt::Formatter<'_>, items: &[Schema]| {
+            items.iter().enumerate().try_for_each(|(i, item)| {
+                let sep = if i > 0 { ", " } else { "" };
+                write!(f, "{}{}", sep, Summary(item))
+            })
+        };
+        match self.0 {
+            Schema::Opaque(name) => f.write_str(name),
+            Schema::Container { name, args } if args.is_empty() => f.write_str(name),
+            Schema::Container { name, args } => {
+                write!(f, "{}<", name)?;
+                list(f, args)?;
+                f.write_str(">")
+            }
+            Schema::Array { len, item } => write!(f, "[{}; {}]", Summary(item), len),
+            Schema::Tuple(items) => {
+                f.write_str("(")?;
+                list(f, items)?;
+                f.write_str(")")
+            }
+            Schema::Struct { name, .. } => write!(f, "struct {}", name),
+            Schema::Enum { name, .. } => write!(f, "enum {}", name),
+            Schema::Recursion { name, .. } => f.write_str(name),
+            Schema::AssumeFrozen => f.write_str("#[assume_frozen]"),
+            Schema::FrozenGenerics(_) => f.write_str("#[assume_frozen(freeze_generics)]"),
+        }
+    }
+}
+
+/// serde attributes of a field or variant, e.g `#[serde(rename = "a", alias = "b", default)]`
+struct SerdeSummary<'a>(&'a Serde);
+
+impl fmt::Display for SerdeSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut attrs = Vec::new();
+        match (&self.0.serialize_name, &self.0.deserialize_name) {
+            (Some(s), Some(d)) if s == d => attrs.push(format!("rename = {:?}", s)),
+            (Some(s), Some(d)) => attrs.push(format!(
+                "rename(serialize = {:?}, deserialize = {:?})",
+                s, d
+            )),
+            (Some(s), None) => attrs.push(format!("rename(serialize = {:?})", s)),
+            (None, Some(d)) => attrs.push(format!("rename(deserialize = {:?})", d)),
+            (None, None) => {}
+        }
+        attrs.extend(self.0.aliases.iter().map(|a| format!("alias = {:?}", a)));
+        if self.0.is_skipped() {
+            attrs.push("skip".to_string());
+        } else {
+            match self.0.serialized {
+                Serialized::Always => {}
+                Serialized::Conditionally => attrs.push("skip_serializing_if".to_string()),
+                Serialized::Never => attrs.push("skip_serializing".to_string()),
+            }
+            match self.0.deserialized {
+                Deserialized::Required => {}
+                Deserialized::Defaulted => attrs.push("default".to_string()),
+                Deserialized::Never => attrs.push("skip_deserializing".to_string()),
+            }
+        }
+        if self.0.flatten {
+            attrs.push("flatten".to_string());
+        }
+        match attrs.is_empty() {
+            true => f.write_str("no #[serde] attributes"),
+            false => write!(f, "#[serde({})]", attrs.join(", ")),
+        }
+    }
+}
+
+fn serde_changed(old: &Serde, new: &Serde, path: &str, changes: &mut Vec<Change>) {
+    if old != new {
+        changes.push(Change {
+            path: path.to_string(),
+            kind: ChangeKind::Changed {
+                old: SerdeSummary(old).to_string(),
+                new: SerdeSummary(new).to_string(),
+            },
+        });
+    }
+}
+
+/// serde representation of a struct or enum, e.g `#[serde(tag = "t")]`
+struct RepresentationSummary<'a>(&'a Representation);
+
+impl fmt::Display for RepresentationSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Representation::Default => f.write_str("default serde representation"),
+            Representation::Transparent => f.write_str("#[serde(transparent)]"),
+            Representation::Internal { tag } => write!(f, "#[serde(tag = {:?})]", tag),
+            Representation::Adjacent { tag, content } => {
+                write!(f, "#[serde(tag = {:?}, content = {:?})]", tag, content)
+            }
+            Representation::Untagged => f.write_str("#[serde(untagged)]"),
+        }
+    }
+}
+
+fn representation_changed(
+    old: &Representation,
+    new: &Representation,
+    path: &str,
+    changes: &mut Vec<Change>,
+) {
+    if old != new {
+        changes.push(Change {
+            path: path.to_string(),
+            kind: ChangeKind::Changed {
+                old: RepresentationSummary(old).to_string(),
+                new: RepresentationSummary(new).to_string(),
+            },
+        });
+    }
+}
+
+fn shape_changed(old: Shape, new: Shape, path: &str, changes: &mut Vec<Change>) {
+    let describe = |shape| match shape {
+        Shape::Unit => "unit",
+        Shape::Tuple => "tuple",
+        Shape::Named => "named fields",
+    };
+    if old != new {
+        changes.push(Change {
+            path: path.to_string(),
+            kind: ChangeKind::Changed {
+                old: describe(old).to_string(),
+                new: describe(new).to_string(),
+            },
+        });
+    }
+}
+
+/// memory layout of a struct or enum, e.g `#[repr(C)] size 8, align 4, offsets (0, 4)`
+struct LayoutSummary<'a>(Option<&'a Layout>);
+
+impl fmt::Display for LayoutSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(layout) = self.0 else {
+            return f.write_str("no #[repr]");
+        };
+        write!(
+            f,
+            "#[repr({})] size {}, align {}",
+            layout.repr, layout.size, layout.align
+        )?;
+        if !layout.offsets.is_empty() {
+            let offsets: Vec<_> = layout.offsets.iter().map(|o| o.to_string()).collect();
+            write!(f, ", offsets ({})", offsets.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+fn layout_changed(
+    old: &Option<Layout>,
+    new: &Option<Layout>,
+    path: &str,
+    changes: &mut Vec<Change>,
+) {
+    if old != new {
+        changes.push(Change {
+            path: path.to_string(),
+            kind: ChangeKind::Changed {
+                old: LayoutSummary(old.as_ref()).to_string(),
+                new: LayoutSummary(new.as_ref()).to_string(),
+            },
+        });
+    }
+}
+
+/// change of `#[frozone(positional)]`, or of the order of the fields
+/// (or variants) found on both sides of positional types
+fn order_changed(
+    (positional, new_positional): (bool, bool),
+    names: Vec<String>,
+    new_names: Vec<String>,
+    path: &str,
+    changes: &mut Vec<Change>,
+) {
+    let describe = |positional| match positional {
+        true => "#[frozone(positional)]",
+        false => "unordered",
+    };
+    let (old, new) = if positional != new_positional {
+        (
+            describe(positional).to_string(),
+            describe(new_positional).to_string(),
+        )
+    } else {
+        let kept: Vec<_> = names.iter().filter(|n| new_names.contains(n)).collect();
+        let new_kept: Vec<_> = new_names.iter().filter(|n| names.contains(n)).collect();
+        if !positional || kept == new_kept {
+            return;
+        }
+        let order = |names: Vec<&String>| {
+            let names: Vec<_> = names.iter().map(|n| n.as_str()).collect();
+            format!("order ({})", names.join(", "))
+        };
+        (order(kept), order(new_kept))
+    };
+    changes.push(Change {
+        path: path.to_string(),
+        kind: ChangeKind::Changed { old, new },
+    });
+}
+
+impl Schema {
+    /// Lists what changed between `self` (the old schema) and `new`,
+    /// ignoring anything that doesn't change the freeze (such as the order
+    /// of fields and variants of non-positional types, or the name of the
+    /// types), along with changes to the serde attributes, shapes and order (frozen by v2)
+    /// and to the memory layout (frozen under [`Profile::Layout`])
+    pub fn diff(&self, new: &Schema) -> Vec<Change> {
+        let path = match self {
+            Schema::Struct { name, .. } | Schema::Enum { name, .. } => name.to_string(),
+            _ => Summary(self).to_string(),
+        };
+        let mut changes = Vec::new();
+        let freezes = (&Freezes::of(self), &Freezes::of(new));
+        diff(self, new, freezes, path, &mut changes);
+        changes
+    }
+}
+
+fn changed(old: &Schema, new: &Schema, path: String, changes: &mut Vec<Change>) {
+    changes.push(Change {
+        path,
+        kind: ChangeKind::Changed {
+            old: Summary(old).to_string(),
+            new: Summary(new).to_string(),
+        },
+    });
+}
+
+/// `freezes`: the ones of `old` and `new` (computed once for the whole
+/// schemas, rather than for each sub-structure compared)
+fn diff(
+    old: &Schema,
+    new: &Schema,
+    freezes: (&Freezes, &Freezes),
+    path: String,
+    changes: &mut Vec<Change>,
+) {
+    // v2 also freezes the serde attributes, and the layout profile the layouts
+    if freezes.0.same(freezes.1) {
+        return;
+    }
+    let inner = || freezes.0.inner.iter().zip(&freezes.1.inner);
+    match (old, new) {
+        (
+            Schema::Container { name, args },
+            Schema::Container {
+                name: new_name,
+                args: new_args,
+            },
+        ) if name == new_name && args.len() == new_args.len() => {
+            for (i, ((old, new), freezes)) in args.iter().zip(new_args).zip(inner()).enumerate() {
+                let path = match args.len() {
+                    1 => path.clone(),
+                    _ => format!("{}<{}>", path, i),
+                };
+                diff(old, new, freezes, path, changes);
+            }
+        }
+        (
+            Schema::Array { len, item },
+            Schema::Array {
+                len: new_len,
+                item: new_item,
+            },
+        ) if len == new_len => {
+            if let Some(freezes) = inner().next() {
+                diff(item, new_item, freezes, path, changes);
+            }
+        }
+        (Schema::Tuple(items), Schema::Tuple(new_items)) if items.len() == new_items.len() => {
+            for (i, ((old, new), freezes)) in items.iter().zip(new_items).zip(inner()).enumerate() {
+                diff(old, new, freezes, format!("{}.{}", path, i), changes);
+            }
+        }
+        (
+            Schema::Struct {
+                shape,
+                fields,
+                serde,
+                positional,
+                layout,
+                ..
+            },
+            Schema::Struct {
+                shape: new_shape,
+                fields: new_fields,
+                serde: new_serde,
+                positional: new_positional,
+                layout: new_layout,
+                ..
+            },
+        ) => {
+            shape_changed(*shape, *new_shape, &path, changes);
+            representation_changed(serde, new_serde, &path, changes);
+            layout_changed(layout, new_layout, &path, changes);
+            let names = |fields: &[Field]| fields.iter().map(|f| f.name.to_string()).collect();
+            order_changed(
+                (*positional, *new_positional),
+                names(fields),
+                names(new_fields),
+                &path,
+                changes,
+            );
+            let field_freezes = (&freezes.0.inner[..], &freezes.1.inner[..]);
+            diff_fields(fields, new_fields, field_freezes, &path, ".", changes);
+        }
+        (
+            Schema::Enum {
+                variants,
+                serde,
+                positional,
+                layout,
+                ..
+            },
+            Schema::Enum {
+                variants: new_variants,
+                serde: new_serde,
+                positional: new_positional,
+                layout: new_layout,
+                ..
+            },
+        ) => {
+            representation_changed(serde, new_serde, &path, changes);
+            layout_changed(layout, new_layout, &path, changes);
+            let names =
+                |variants: &[Variant]| variants.iter().map(|v| v.name.to_string()).collect();
+            order_changed(
+                (*positional, *new_positional),
+                names(variants),
+                names(new_variants),
+                &path,
+                changes,
+            );
+            diff_variants(variants, new_variants, freezes, &path, changes);
+        }
+        _ => changed(old, new, path, changes),
+    }
+}
+
+/// fields (and variants) are matched by name, the ones only found on one side
+/// are reported as renamed if the other side has an unmatched one with the same
+/// freeze (`freezes` being the ones of the fields)
+fn diff_fields(
+    fields: &[Field],
+    new_fields: &[Field],
+    freezes: (&[Freezes], &[Freezes]),
+    path: &str,
+    separator: &str,
+    changes: &mut Vec<Change>,
+) {
+    let fields: Vec<_> = fields.iter().zip(freezes.0).collect();
+    let new_fields: Vec<_> = new_fields.iter().zip(freezes.1).collect();
+    let removed: Vec<_> = fields
+        .iter()
+        .filter(|(f, _)| !new_fields.iter().any(|(n, _)| n.name == f.name))
+        .collect();
+    let mut added: Vec<_> = new_fields
+        .iter()
+        .filter(|(n, _)| !fields.iter().any(|(f, _)| f.name == n.name))
+        .collect();
+    for (f, freezes) in &fields {
+        if let Some((n, new_freezes)) = new_fields.iter().find(|(n, _)| n.name == f.name) {
+            let path = format!("{}{}{}", path, separator, f.name);
+            serde_changed(&f.serde, &n.serde, &path, changes);
+            diff(&f.schema, &n.schema, (freezes, new_freezes), path, changes);
+        }
+    }
+    for (f, freezes) in removed {
+        let path = format!("{}{}{}", path, separator, f.name);
+        let kind = match added.iter().position(|(_, n)| n.v1 == freezes.v1) {
+            Some(i) => ChangeKind::Renamed {
+                new: added.remove(i).0.name.to_string(),
+            },
+            None => ChangeKind::Removed,
+        };
+        changes.push(Change { path, kind });
+    }
+    for (n, _) in added {
+        let path = format!("{}{}{}", path, separator, n.name);
+        changes.push(Change {
+            path,
+            kind: ChangeKind::Added,
+        });
+    }
+}
+
+/// `freezes`: the ones of the old and new enums
+fn diff_variants(
+    variants: &[Variant],
+    new_variants: &[Variant],
+    freezes: (&Freezes, &Freezes),
+    path: &str,
+    changes: &mut Vec<Change>,
+) {
+    let variants: Vec<_> = variants
+        .iter()
+        .zip(freezes.0.of_variants(variants))
+        .collect();
+    let new_variants: Vec<_> = new_variants
+        .iter()
+        .zip(freezes.1.of_variants(new_variants))
+        .collect();
+    // renamed variants are matched by freeze, as the fields are
+    let removed = variants
+        .iter()
+        .filter(|(v, _)| !new_variants.iter().any(|(n, _)| n.name == v.name));
+    let mut added: Vec<_> = new_variants
+        .iter()
+        .filter(|(n, _)| !variants.iter().any(|(v, _)| v.name == n.name))
+        .map(|(n, fields)| (n, n.cached_freeze(fields)))
+        .collect();
+    for (v, fields) in removed {
+        let freeze = v.cached_freeze(fields);
+        let kind = match added.iter().position(|(_, n)| *n == freeze) {
+            Some(i) => ChangeKind::Renamed {
+                new: added.remove(i).0.name.to_string(),
+            },
+            None => ChangeKind::Removed,
+        };
+        changes.push(Change {
+            path: format!("{}::{}", path, v.name),
+            kind,
+        });
+    }
+    for (n, _) in added {
+        changes.push(Change {
+            path: format!("{}::{}", path, n.name),
+            kind: ChangeKind::Added,
+        });
+    }
+
+    for (v, fields) in &variants {
+        let Some((n, new_fields)) = new_variants.iter().find(|(n, _)| n.name == v.name) else {
+            continue;
+        };
+        let path = format!("{}::{}", path, v.name);
+        shape_changed(v.shape, n.shape, &path, changes);
+        serde_changed(&v.serde, &n.serde, &path, changes);
+        if v.assume_frozen != n.assume_frozen || v.discriminant != n.discriminant {
+            let describe = |v: &Variant| match (v.assume_frozen, v.discriminant) {
+                (true, _) => "#[assume_frozen]".to_string(),
+                (false, Some(d)) => format!("= {}", d.value),
+                (false, None) => "no discriminant".to_string(),
+            };
+            changes.push(Change {
+                path: path.clone(),
+                kind: ChangeKind::Changed {
+                    old: describe(v),
+                    new: describe(n),
+                },
+            });
+        }
+        if v.shape == Shape::Named && n.shape == Shape::Named {
+            // named fields are matched by name, as the ones of structs
+            diff_fields(
+                &v.fields,
+                &n.fields,
+                (fields, new_fields),
+                &path,
+                ".",
+                changes,
+            );
+            continue;
+        }
+        for (f, new_f) in v.fields.iter().zip(&n.fields) {
+            let path = format!("{}.{}", path, f.name);
+            serde_changed(&f.serde, &new_f.serde, &path, changes);
+        }
+        if v.cached_freeze(fields) == n.cached_freeze(new_fields) {
+            continue;
+        }
+        // unnamed variant fields are positional
+        let freezes = fields.iter().zip(*new_fields);
+        for ((f, new_f), freezes) in v.fields.iter().zip(&n.fields).zip(freezes) {
+            let path = format!("{}.{}", path, f.name);
+            diff(&f.schema, &new_f.schema, freezes, path, changes);
+        }
+        for f in v.fields.iter().skip(n.fields.len()) {
+            changes.push(Change {
+                path: format!("{}.{}", path, f.name),
+                kind: ChangeKind::Removed,
+            });
+        }
+        for f in n.fields.iter().skip(v.fields.len()) {
+            changes.push(Change {
+                path: format!("{}.{}", path, f.name),
+                kind: ChangeKind::Added,
+            });
+        }
+    }
+}
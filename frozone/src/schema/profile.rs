@@ -0,0 +1,196 @@
+//! Profiles: which types are considered equivalent when freezing, and what is frozen
+use super::{Field, GenericArgs, Schema, Variant};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+/// Which types are considered equivalent when freezing, see [`Schema::with_profile`]
+/// and [`Schema::freeze_with`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Profile {
+    /// every type is frozen as itself: `Vec<T>` and `VecDeque<T>`, or
+    /// `Box<T>` and `T`, have different freezes
+    #[default]
+    Strict,
+    /// types that serde (de)serializes the same way are frozen the same:
+    /// - sequences (`VecDeque<T>`, `[T]`, `&[T]`, `BTreeSet<T>`, `BinaryHeap<T>`) as `Vec<T>`
+    /// - smart pointers, references and cells (`Box<T>`, `Rc<T>`, `Arc<T>`, `&T`,
+    ///   `Cow<T>`, `Cell<T>`, `RefCell<T>`, `Mutex<T>`, `RwLock<T>`) as `T`,
+    ///   as well as `Wrapping<T>` and `Reverse<T>`
+    /// - atomics and `NonZero` integers as their integer
+    /// - `str`, `&str`, `Path` and `PathBuf` as `String`
+    Serialization,
+    /// [`Profile::Strict`], along with the memory layout of the `#[repr(...)]`
+    /// types (see [`Layout`](super::Layout)) and the order of the fields and
+    /// variants of every type (see [`Schema::positional`]), for types shared
+    /// with C code or mapped from disk
+    ///
+    /// Unlike the other profiles, the freezes depend on the target.
+    Layout,
+}
+
+/// containers (de)serialized as their only generic argument
+const TRANSPARENT: &[&str] = &[
+    "Box",
+    "alloc::rc::Rc",
+    "alloc::sync::Arc",
+    "&",
+    "Cow",
+    "Cell",
+    "RefCell",
+    "Mutex",
+    "RwLock",
+    "Wrapping",
+    "Reverse",
+];
+
+/// containers (de)serialized as a sequence of their generic argument
+const SEQUENCES: &[&str] = &["VecDeque", "[]", "&[]", "BTreeSet", "BinaryHeap"];
+
+/// opaque types (de)serialized as a string
+const STRINGS: &[&str] = &["str", "&str", "std::path::Path", "std::path::PathBuf"];
+
+/// name of the integer (or `bool`) an atomic or a `NonZero` is (de)serialized as
+fn integer(name: &str) -> Option<&str> {
+    if let Some(atomic) = name.strip_prefix("core::sync::atomic::Atomic") {
+        // `AtomicU32` -> `u32`, `AtomicBool` -> `bool`
+        return match atomic {
+            "Bool" => Some("bool"),
+            "I8" => Some("i8"),
+            "I16" => Some("i16"),
+            "I32" => Some("i32"),
+            "I64" => Some("i64"),
+            "Isize" => Some("isize"),
+            "U8" => Some("u8"),
+            "U16" => Some("u16"),
+            "U32" => Some("u32"),
+            "U64" => Some("u64"),
+            "Usize" => Some("usize"),
+            _ => None,
+        };
+    }
+    name.strip_prefix("core::num::nonzero::NonZero<")?
+        .strip_suffix('>')
+}
+
+impl Schema {
+    /// Rewrites the schema so that the types equivalent under `profile` have
+    /// the same schema (and so the same freeze), e.g `VecDeque<u8>` becomes
+    /// `Vec<u8>` under [`Profile::Serialization`]
+    ///
+    /// [`Profile::Strict`] leaves the schema unchanged, and [`Profile::Layout`]
+    /// makes it [positional](Schema::positional) (its layouts being frozen by
+    /// [`Schema::freeze_with`]).
+    pub fn with_profile(&self, profile: Profile) -> Schema {
+        match profile {
+            Profile::Strict => self.clone(),
+            Profile::Serialization => self.serialized(),
+            Profile::Layout => self.positional(),
+        }
+    }
+
+    /// schema under [`Profile::Serialization`]
+    fn serialized(&self) -> Schema {
+        let all = |schemas: &[Schema]| schemas.iter().map(Schema::serialized).collect::<Vec<_>>();
+        match self {
+            Schema::Opaque(name) if STRINGS.contains(&&**name) => {
+                Schema::Opaque("alloc::string::String".into())
+            }
+            Schema::Opaque(name) => match integer(name) {
+                Some(integer) => Schema::Opaque(Cow::Owned(integer.into())),
+                None => self.clone(),
+            },
+            Schema::Container { name, args } if args.len() == 1 => {
+                if TRANSPARENT.contains(&&**name) {
+                    args[0].serialized()
+                } else if SEQUENCES.contains(&&**name) {
+                    Schema::Container {
+                        name: "Vec".into(),
+                        args: all(args),
+                    }
+                } else {
+                    Schema::Container {
+                        name: name.clone(),
+                        args: all(args),
+                    }
+                }
+            }
+            Schema::Container { name, args } => Schema::Container {
+                name: name.clone(),
+                args: all(args),
+            },
+            Schema::Array { len, item } => Schema::Array {
+                len: *len,
+                item: Box::new(item.serialized()),
+            },
+            Schema::Tuple(items) => Schema::Tuple(all(items)),
+            Schema::Struct {
+                name,
+                shape,
+                fields,
+                serde,
+                positional,
+                layout,
+            } => Schema::Struct {
+                name: name.clone(),
+                shape: *shape,
+                fields: fields.iter().map(Field::serialized).collect(),
+                serde: serde.clone(),
+                positional: *positional,
+                layout: layout.clone(),
+            },
+            Schema::Enum {
+                name,
+                variants,
+                serde,
+                positional,
+                layout,
+            } => Schema::Enum {
+                name: name.clone(),
+                variants: variants
+                    .iter()
+                    .map(|v| Variant {
+                        fields: v.fields.iter().map(Field::serialized).collect(),
+                        ..v.clone()
+                    })
+                    .collect(),
+                serde: serde.clone(),
+                positional: *positional,
+                layout: layout.clone(),
+            },
+            Schema::Recursion { .. } | Schema::AssumeFrozen => self.clone(),
+            Schema::FrozenGenerics(segments) => Schema::FrozenGenerics(
+                segments
+                    .iter()
+                    .map(|segment| match segment {
+                        GenericArgs::None => GenericArgs::None,
+                        GenericArgs::AngleBracketed { args, bindings } => {
+                            GenericArgs::AngleBracketed {
+                                args: all(args),
+                                bindings: bindings
+                                    .iter()
+                                    .map(|(name, schema)| (name.clone(), schema.serialized()))
+                                    .collect(),
+                            }
+                        }
+                        GenericArgs::Parenthesized { inputs, output } => {
+                            GenericArgs::Parenthesized {
+                                inputs: all(inputs),
+                                output: Box::new(output.serialized()),
+                            }
+                        }
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl Field {
+    fn serialized(&self) -> Field {
+        Field {
+            schema: self.schema.serialized(),
+            ..self.clone()
+        }
+    }
+}
@@ -0,0 +1,675 @@
+//! Text form of a [`Schema`], so that it can be stored and compared later on
+//!
+//! The format is a simple s-expression, one node per parenthesized list, e.g
+//! `(struct "MyType" named (field "a" (opaque "u64")))`
+use super::{
+    Deserialized, Discriminant, Field, GenericArgs, Layout, Representation, Schema, Serde,
+    Serialized, Shape, Variant,
+};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+/// lists longer than that are split over several lines
+const MAX_LINE_WIDTH: usize = 100;
+
+enum Sexp {
+    /// keyword or number
+    Symbol(String),
+    /// quoted string
+    Str(String),
+    /// parenthesized list, along with its offset in the parsed input
+    List(Vec<Sexp>, usize),
+}
+
+fn sym(s: impl ToString) -> Sexp {
+    Sexp::Symbol(s.to_string())
+}
+
+fn string(s: &str) -> Sexp {
+    Sexp::Str(s.into())
+}
+
+fn list(items: Vec<Sexp>) -> Sexp {
+    Sexp::List(items, 0)
+}
+
+impl Sexp {
+    fn write_flat(&self, out: &mut String) {
+        match self {
+            Sexp::Symbol(s) => out.push_str(s),
+            Sexp::Str(s) => {
+                out.push('"');
+                for c in s.chars() {
+                    if c == '"' || c == '\\' {
+                        out.push('\\');
+                    }
+                    out.push(c);
+                }
+                out.push('"');
+            }
+            Sexp::List(items, _) => {
+                out.push('(');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(' ');
+                    }
+                    item.write_flat(out);
+                }
+                out.push(')');
+            }
+        }
+    }
+
+    /// writes the list on one line if it fits, else its leading atoms
+    /// on the first line and every other item on its own (indented) line
+    fn write_pretty(&self, out: &mut String, indent: usize) {
+        let mut flat = String::new();
+        self.write_flat(&mut flat);
+        let Sexp::List(items, _) = self else {
+            out.push_str(&flat);
+            return;
+        };
+        if indent + flat.len() <= MAX_LINE_WIDTH {
+            out.push_str(&flat);
+            return;
+        }
+        let atoms = items
+            .iter()
+            .take_while(|i| !matches!(i, Sexp::List(..)))
+            .count();
+        out.push('(');
+        for (i, item) in items.iter().enumerate() {
+            if i < atoms {
+                if i > 0 {
+                    out.push(' ');
+                }
+                item.write_flat(out);
+            } else {
+                out.push('\n');
+                (0..indent + 2).for_each(|_| out.push(' '));
+                item.write_pretty(out, indent + 2);
+            }
+        }
+        out.push(')');
+    }
+}
+
+fn shape_sexp(shape: Shape) -> Sexp {
+    sym(match shape {
+        Shape::Unit => "unit",
+        Shape::Tuple => "tuple",
+        Shape::Named => "named",
+    })
+}
+
+/// `(serde (serialize "a") (deserialize "b") (alias "c") ... (skip_serializing_if) (default))`,
+/// if not the default
+fn serde_sexp(serde: &Serde) -> Option<Sexp> {
+    if *serde == Serde::default() {
+        return None;
+    }
+    let mut l = vec![sym("serde")];
+    if let Some(name) = &serde.serialize_name {
+        l.push(list(vec![sym("serialize"), string(name)]));
+    }
+    if let Some(name) = &serde.deserialize_name {
+        l.push(list(vec![sym("deserialize"), string(name)]));
+    }
+    l.extend(
+        serde
+            .aliases
+            .iter()
+            .map(|a| list(vec![sym("alias"), string(a)])),
+    );
+    match serde.serialized {
+        Serialized::Always => {}
+        Serialized::Conditionally => l.push(list(vec![sym("skip_serializing_if")])),
+        Serialized::Never => l.push(list(vec![sym("skip_serializing")])),
+    }
+    match serde.deserialized {
+        Deserialized::Required => {}
+        Deserialized::Defaulted => l.push(list(vec![sym("default")])),
+        Deserialized::Never => l.push(list(vec![sym("skip_deserializing")])),
+    }
+    if serde.flatten {
+        l.push(list(vec![sym("flatten")]));
+    }
+    Some(list(l))
+}
+
+/// `(serde (tag "t") (content "c"))`, `(serde (transparent))` or
+/// `(serde (untagged))`, if not the default
+fn representation_sexp(serde: &Representation) -> Option<Sexp> {
+    let attrs = match serde {
+        Representation::Default => return None,
+        Representation::Transparent => vec![list(vec![sym("transparent")])],
+        Representation::Internal { tag } => vec![list(vec![sym("tag"), string(tag)])],
+        Representation::Adjacent { tag, content } => vec![
+            list(vec![sym("tag"), string(tag)]),
+            list(vec![sym("content"), string(content)]),
+        ],
+        Representation::Untagged => vec![list(vec![sym("untagged")])],
+    };
+    let mut l = vec![sym("serde")];
+    l.extend(attrs);
+    Some(list(l))
+}
+
+/// `(layout "repr" size align offsets...)`
+fn layout_sexp(layout: &Layout) -> Sexp {
+    let mut l = vec![
+        sym("layout"),
+        string(&layout.repr),
+        sym(layout.size),
+        sym(layout.align),
+    ];
+    l.extend(layout.offsets.iter().map(sym));
+    list(l)
+}
+
+fn field_sexp(f: &Field) -> Sexp {
+    let mut field = vec![sym("field"), string(&f.name)];
+    field.extend(serde_sexp(&f.serde));
+    field.push(f.schema.to_sexp());
+    list(field)
+}
+
+fn variant_sexp(v: &Variant) -> Sexp {
+    let mut variant = vec![sym("variant"), string(&v.name), shape_sexp(v.shape)];
+    if v.assume_frozen {
+        variant.push(sym("assume_frozen"));
+    }
+    if let Some(d) = v.discriminant {
+        variant.push(list(vec![sym("discriminant"), sym(d.value), sym(d.size)]));
+    }
+    variant.extend(serde_sexp(&v.serde));
+    variant.extend(v.fields.iter().map(field_sexp));
+    list(variant)
+}
+
+fn generic_args_sexp(args: &GenericArgs) -> Sexp {
+    match args {
+        GenericArgs::None => list(vec![sym("none")]),
+        GenericArgs::AngleBracketed { args, bindings } => {
+            let mut l = vec![sym("angle_bracketed")];
+            l.extend(args.iter().map(Schema::to_sexp));
+            l.extend(
+                bindings.iter().map(|(name, schema)| {
+                    list(vec![sym("binding"), string(name), schema.to_sexp()])
+                }),
+            );
+            list(l)
+        }
+        GenericArgs::Parenthesized { inputs, output } => {
+            let mut l = vec![sym("parenthesized")];
+            l.extend(inputs.iter().map(Schema::to_sexp));
+            l.push(output.to_sexp());
+            list(l)
+        }
+    }
+}
+
+impl Schema {
+    fn to_sexp(&self) -> Sexp {
+        let mut l = Vec::new();
+        match self {
+            Schema::Opaque(name) => l.extend([sym("opaque"), string(name)]),
+            Schema::Container { name, args } => {
+                l.extend([sym("container"), string(name)]);
+                l.extend(args.iter().map(Schema::to_sexp));
+            }
+            Schema::Array { len, item } => l.extend([sym("array"), sym(len), item.to_sexp()]),
+            Schema::Tuple(items) => {
+                l.push(sym("tuple"));
+                l.extend(items.iter().map(Schema::to_sexp));
+            }
+            Schema::Struct {
+                name,
+                shape,
+                fields,
+                serde,
+                positional,
+                layout,
+            } => {
+                l.extend([sym("struct"), string(name), shape_sexp(*shape)]);
+                if *positional {
+                    l.push(sym("positional"));
+                }
+                l.extend(representation_sexp(serde));
+                l.extend(layout.as_ref().map(layout_sexp));
+                l.extend(fields.iter().map(field_sexp));
+            }
+            Schema::Enum {
+                name,
+                variants,
+                serde,
+                positional,
+                layout,
+            } => {
+                l.extend([sym("enum"), string(name)]);
+                if *positional {
+                    l.push(sym("positional"));
+                }
+                l.extend(representation_sexp(serde));
+                l.extend(layout.as_ref().map(layout_sexp));
+                l.extend(variants.iter().map(variant_sexp));
+            }
+            Schema::Recursion { name, depth } => {
+                l.extend([sym("recursion"), string(name), sym(depth)]);
+            }
+            Schema::AssumeFrozen => l.push(sym("assume_frozen")),
+            Schema::FrozenGenerics(segments) => {
+                l.push(sym("frozen_generics"));
+                l.extend(segments.iter().map(generic_args_sexp));
+            }
+        }
+        list(l)
+    }
+}
+
+/// Pretty-printed text form, that can be parsed back with [`str::parse`]
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = String::new();
+        self.to_sexp().write_pretty(&mut out, 0);
+        f.write_str(&out)
+    }
+}
+
+/// Error returned when parsing the text form of a [`Schema`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// byte offset in the input of the (innermost) list the error was found in
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// parses the text into s-expressions
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &'static str) -> ParseError {
+        ParseError {
+            offset: self.pos,
+            message,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+        self.input[self.pos..].chars().next()
+    }
+
+    fn parse(&mut self) -> Result<Sexp, ParseError> {
+        match self.peek() {
+            None => Err(self.error("unexpected end of input")),
+            Some('(') => {
+                let offset = self.pos;
+                self.pos += 1;
+                let mut items = Vec::new();
+                loop {
+                    match self.peek() {
+                        Some(')') => {
+                            self.pos += 1;
+                            return Ok(Sexp::List(items, offset));
+                        }
+                        None => return Err(self.error("unclosed parenthesis")),
+                        _ => items.push(self.parse()?),
+                    }
+                }
+            }
+            Some(')') => Err(self.error("unexpected closing parenthesis")),
+            Some('"') => {
+                self.pos += 1;
+                let mut s = String::new();
+                let mut chars = self.input[self.pos..].char_indices();
+                while let Some((i, c)) = chars.next() {
+                    match c {
+                        '"' => {
+                            self.pos += i + 1;
+                            return Ok(Sexp::Str(s));
+                        }
+                        '\\' => match chars.next() {
+                            Some((_, c)) => s.push(c),
+                            None => break,
+                        },
+                        c => s.push(c),
+                    }
+                }
+                Err(self.error("unterminated string"))
+            }
+            Some(_) => {
+                let rest = &self.input[self.pos..];
+                let len = rest
+                    .find(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == '"')
+                    .unwrap_or(rest.len());
+                self.pos += len;
+                Ok(Sexp::Symbol(rest[..len].into()))
+            }
+        }
+    }
+}
+
+/// converts the items of a parsed list back to the schema types
+struct Items<'a> {
+    items: core::slice::Iter<'a, Sexp>,
+    offset: usize,
+}
+
+impl<'a> Items<'a> {
+    fn of(sexp: &'a Sexp) -> Result<Self, ParseError> {
+        match sexp {
+            Sexp::List(items, offset) => Ok(Items {
+                items: items.iter(),
+                offset: *offset,
+            }),
+            // atoms are always found inside a list (whose offset is unknown here)
+            _ => Err(ParseError {
+                offset: 0,
+                message: "expected a list",
+            }),
+        }
+    }
+
+    fn error(&self, message: &'static str) -> ParseError {
+        ParseError {
+            offset: self.offset,
+            message,
+        }
+    }
+
+    fn end(&mut self) -> Result<(), ParseError> {
+        match self.items.next() {
+            None => Ok(()),
+            Some(_) => Err(self.error("unexpected trailing item")),
+        }
+    }
+
+    fn peek_symbol(&self) -> Option<&'a str> {
+        match self.items.clone().next() {
+            Some(Sexp::Symbol(s)) => Some(s),
+            Some(Sexp::List(items, _)) => match items.first() {
+                Some(Sexp::Symbol(s)) => Some(s),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn symbol(&mut self) -> Result<&'a str, ParseError> {
+        match self.items.next() {
+            Some(Sexp::Symbol(s)) => Ok(s),
+            _ => Err(self.error("expected a symbol")),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        match self.symbol()? {
+            s if s == keyword => Ok(()),
+            _ => Err(self.error("unexpected keyword")),
+        }
+    }
+
+    fn number<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        self.symbol()?
+            .parse()
+            .map_err(|_| self.error("expected a number"))
+    }
+
+    fn string(&mut self) -> Result<Cow<'static, str>, ParseError> {
+        match self.items.next() {
+            Some(Sexp::Str(s)) => Ok(s.clone().into()),
+            _ => Err(self.error("expected a string")),
+        }
+    }
+
+    fn shape(&mut self) -> Result<Shape, ParseError> {
+        match self.symbol()? {
+            "unit" => Ok(Shape::Unit),
+            "tuple" => Ok(Shape::Tuple),
+            "named" => Ok(Shape::Named),
+            _ => Err(self.error("unknown shape")),
+        }
+    }
+
+    fn list(&mut self) -> Result<Items<'a>, ParseError> {
+        match self.items.next() {
+            Some(sexp) => Items::of(sexp),
+            None => Err(self.error("expected a list")),
+        }
+    }
+
+    /// converts all the remaining items
+    fn rest<T>(
+        &mut self,
+        f: impl Fn(Items<'a>) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        self.items.by_ref().map(|s| f(Items::of(s)?)).collect()
+    }
+
+    fn schema(&mut self) -> Result<Schema, ParseError> {
+        self.list()?.into_schema()
+    }
+
+    /// optional `(serde ...)` list
+    fn serde(&mut self) -> Result<Serde, ParseError> {
+        let mut serde = Serde::default();
+        if self.peek_symbol() != Some("serde") {
+            return Ok(serde);
+        }
+        let mut l = self.list()?;
+        l.keyword("serde")?;
+        for item in l.rest(Ok)? {
+            let mut item = item;
+            match item.symbol()? {
+                "serialize" => serde.serialize_name = Some(item.string()?),
+                "deserialize" => serde.deserialize_name = Some(item.string()?),
+                "alias" => serde.aliases.push(item.string()?),
+                "skip_serializing_if" => serde.serialized = Serialized::Conditionally,
+                "skip_serializing" => serde.serialized = Serialized::Never,
+                "default" => serde.deserialized = Deserialized::Defaulted,
+                "skip_deserializing" => serde.deserialized = Deserialized::Never,
+                "flatten" => serde.flatten = true,
+                _ => return Err(item.error("unknown serde attribute")),
+            }
+            item.end()?;
+        }
+        Ok(serde)
+    }
+
+    /// optional `positional` symbol of a struct or enum
+    fn positional(&mut self) -> Result<bool, ParseError> {
+        let positional = self.peek_symbol() == Some("positional");
+        if positional {
+            self.symbol()?;
+        }
+        Ok(positional)
+    }
+
+    /// optional `(serde ...)` list of a struct or enum
+    fn representation(&mut self) -> Result<Representation, ParseError> {
+        if self.peek_symbol() != Some("serde") {
+            return Ok(Representation::Default);
+        }
+        let mut l = self.list()?;
+        l.keyword("serde")?;
+        let (mut tag, mut content, mut transparent, mut untagged) = (None, None, false, false);
+        for item in l.rest(Ok)? {
+            let mut item = item;
+            match item.symbol()? {
+                "tag" => tag = Some(item.string()?),
+                "content" => content = Some(item.string()?),
+                "transparent" => transparent = true,
+                "untagged" => untagged = true,
+                _ => return Err(item.error("unknown serde attribute")),
+            }
+            item.end()?;
+        }
+        Ok(match (tag, content, transparent, untagged) {
+            (None, None, false, false) => Representation::Default,
+            (None, None, true, false) => Representation::Transparent,
+            (Some(tag), None, false, false) => Representation::Internal { tag },
+            (Some(tag), Some(content), false, false) => Representation::Adjacent { tag, content },
+            (None, None, false, true) => Representation::Untagged,
+            _ => return Err(l.error("invalid serde representation")),
+        })
+    }
+
+    /// optional `(layout ...)` list of a struct or enum
+    fn layout(&mut self) -> Result<Option<Layout>, ParseError> {
+        if self.peek_symbol() != Some("layout") {
+            return Ok(None);
+        }
+        let mut l = self.list()?;
+        l.keyword("layout")?;
+        let mut layout = Layout {
+            repr: l.string()?,
+            size: l.number()?,
+            align: l.number()?,
+            offsets: Vec::new(),
+        };
+        while l.items.len() > 0 {
+            layout.offsets.push(l.number()?);
+        }
+        Ok(Some(layout))
+    }
+
+    fn field(mut self) -> Result<Field, ParseError> {
+        self.keyword("field")?;
+        let field = Field {
+            name: self.string()?,
+            serde: self.serde()?,
+            schema: self.schema()?,
+        };
+        self.end()?;
+        Ok(field)
+    }
+
+    fn variant(mut self) -> Result<Variant, ParseError> {
+        self.keyword("variant")?;
+        let name = self.string()?;
+        let shape = self.shape()?;
+        let assume_frozen = self.peek_symbol() == Some("assume_frozen");
+        if assume_frozen {
+            self.symbol()?;
+        }
+        let mut discriminant = None;
+        if self.peek_symbol() == Some("discriminant") {
+            let mut d = self.list()?;
+            d.keyword("discriminant")?;
+            discriminant = Some(Discriminant {
+                value: d.number()?,
+                size: d.number()?,
+            });
+            d.end()?;
+        }
+        let serde = self.serde()?;
+        Ok(Variant {
+            name,
+            discriminant,
+            shape,
+            fields: self.rest(Items::field)?,
+            assume_frozen,
+            serde,
+        })
+    }
+
+    fn generic_args(mut self) -> Result<GenericArgs, ParseError> {
+        Ok(match self.symbol()? {
+            "none" => GenericArgs::None,
+            "angle_bracketed" => {
+                let (mut args, mut bindings) = (Vec::new(), Vec::new());
+                for mut item in self.rest(Ok)? {
+                    if item.peek_symbol() == Some("binding") {
+                        item.keyword("binding")?;
+                        bindings.push((item.string()?, item.schema()?));
+                        item.end()?;
+                    } else {
+                        args.push(item.into_schema()?);
+                    }
+                }
+                GenericArgs::AngleBracketed { args, bindings }
+            }
+            "parenthesized" => {
+                let mut inputs = self.rest(Items::into_schema)?;
+                let output = inputs
+                    .pop()
+                    .ok_or_else(|| self.error("expected an output type"))?;
+                GenericArgs::Parenthesized {
+                    inputs,
+                    output: Box::new(output),
+                }
+            }
+            _ => return Err(self.error("unknown generic arguments")),
+        })
+    }
+
+    fn into_schema(mut self) -> Result<Schema, ParseError> {
+        let schema = match self.symbol()? {
+            "opaque" => Schema::Opaque(self.string()?),
+            "container" => Schema::Container {
+                name: self.string()?,
+                args: self.rest(Items::into_schema)?,
+            },
+            "array" => Schema::Array {
+                len: self.number()?,
+                item: Box::new(self.schema()?),
+            },
+            "tuple" => Schema::Tuple(self.rest(Items::into_schema)?),
+            "struct" => Schema::Struct {
+                name: self.string()?,
+                shape: self.shape()?,
+                positional: self.positional()?,
+                serde: self.representation()?,
+                layout: self.layout()?,
+                fields: self.rest(Items::field)?,
+            },
+            "enum" => Schema::Enum {
+                name: self.string()?,
+                positional: self.positional()?,
+                serde: self.representation()?,
+                layout: self.layout()?,
+                variants: self.rest(Items::variant)?,
+            },
+            "recursion" => Schema::Recursion {
+                name: self.string()?,
+                depth: self.number()?,
+            },
+            "assume_frozen" => Schema::AssumeFrozen,
+            "frozen_generics" => Schema::FrozenGenerics(self.rest(Items::generic_args)?),
+            _ => return Err(self.error("unknown schema node")),
+        };
+        self.end()?;
+        Ok(schema)
+    }
+}
+
+impl FromStr for Schema {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { input: s, pos: 0 };
+        let sexp = parser.parse()?;
+        if parser.peek().is_some() {
+            return Err(parser.error("unexpected trailing input"));
+        }
+        Items::of(&sexp)?.into_schema()
+    }
+}
@@ -0,0 +1,126 @@
+//! Walk over a [`Schema`] along with the freeze of each sub-structure,
+//! what [`Freezable::display`](crate::Freezable::display) prints
+use super::{Freezes, Schema};
+use core::fmt;
+
+/// Receives the structs and enums of a schema, their fields and variants
+/// along with their freeze, in the order [`Schema::visit`] walks them
+/// (all methods do nothing by default)
+///
+/// `depth` is the indentation level of what's visited: the fields and variants
+/// of a type are at its level, the fields of a variant one level deeper, and
+/// the types nested in a field one level deeper than the field
+pub trait Visitor {
+    /// a struct or enum, before its fields or variants
+    fn visit_type(&mut self, depth: usize, name: &str) -> fmt::Result {
+        let _ = (depth, name);
+        Ok(())
+    }
+
+    /// a field of a struct, before the types it contains
+    fn visit_field(&mut self, depth: usize, name: &str, freeze: u64) -> fmt::Result {
+        let _ = (depth, name, freeze);
+        Ok(())
+    }
+
+    /// a variant of an enum (`freeze` being the one of its content),
+    /// before its fields
+    fn visit_variant(&mut self, depth: usize, name: &str, freeze: u64) -> fmt::Result {
+        let _ = (depth, name, freeze);
+        Ok(())
+    }
+
+    /// a field of an enum variant, before the types it contains
+    fn visit_variant_field(
+        &mut self,
+        depth: usize,
+        variant: &str,
+        name: &str,
+        freeze: u64,
+    ) -> fmt::Result {
+        let _ = (depth, variant, name, freeze);
+        Ok(())
+    }
+}
+
+/// [`Visitor`] writing the text [`Freezable::display`](crate::Freezable::display)
+/// prints to any [`fmt::Write`] (e.g a `String`)
+#[derive(Debug, Default)]
+pub struct Printer<W>(pub W);
+
+impl<W: fmt::Write> Visitor for Printer<W> {
+    fn visit_type(&mut self, depth: usize, name: &str) -> fmt::Result {
+        writeln!(self.0, "{:\t<2$}({})", "", name, depth)
+    }
+
+    fn visit_field(&mut self, depth: usize, name: &str, freeze: u64) -> fmt::Result {
+        writeln!(self.0, "{:\t<3$} - {} : {:#018x}", "", name, freeze, depth)
+    }
+
+    fn visit_variant(&mut self, depth: usize, name: &str, freeze: u64) -> fmt::Result {
+        writeln!(self.0, "{:\t<3$} - {} : {:#018x}", "", name, freeze, depth)
+    }
+
+    fn visit_variant_field(
+        &mut self,
+        depth: usize,
+        variant: &str,
+        name: &str,
+        freeze: u64,
+    ) -> fmt::Result {
+        writeln!(
+            self.0,
+            "{:\t<4$}({}.{}): {:#018x}",
+            "", variant, name, freeze, depth
+        )
+    }
+}
+
+impl Schema {
+    /// Walks the structs and enums of the schema, see [`Visitor`]
+    /// (stops at the first error returned by the visitor)
+    pub fn visit(&self, visitor: &mut dyn Visitor) -> fmt::Result {
+        self.visit_at(0, &Freezes::of(self), visitor)
+    }
+
+    fn visit_at(&self, depth: usize, freezes: &Freezes, visitor: &mut dyn Visitor) -> fmt::Result {
+        let mut inner = freezes.inner.iter();
+        match self {
+            Schema::Struct { name, fields, .. } => {
+                visitor.visit_type(depth, name)?;
+                for (f, freezes) in fields.iter().zip(inner) {
+                    visitor.visit_field(depth, &f.name, freezes.v1)?;
+                    f.schema.visit_at(depth + 1, freezes, visitor)?;
+                }
+            }
+            Schema::Enum { name, variants, .. } => {
+                visitor.visit_type(depth, name)?;
+                for (v, fields) in variants.iter().zip(freezes.of_variants(variants)) {
+                    visitor.visit_variant(depth, &v.name, v.cached_freeze(fields))?;
+                    for (f, freezes) in v.fields.iter().zip(fields) {
+                        let freeze = freezes.v1;
+                        visitor.visit_variant_field(depth + 1, &v.name, &f.name, freeze)?;
+                        f.schema.visit_at(depth + 2, freezes, visitor)?;
+                    }
+                }
+            }
+            Schema::Container { args, .. } => {
+                for (a, freezes) in args.iter().zip(inner) {
+                    a.visit_at(depth, freezes, visitor)?;
+                }
+            }
+            Schema::Array { item, .. } => {
+                if let Some(freezes) = inner.next() {
+                    item.visit_at(depth, freezes, visitor)?;
+                }
+            }
+            Schema::Tuple(items) => {
+                for (i, freezes) in items.iter().zip(inner) {
+                    i.visit_at(depth, freezes, visitor)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -4,61 +4,172 @@ mod primitive;
 #[cfg(feature = "std")]
 mod std;
 
+/// generic types frozen by their name (explicit, as `stringify!` isn't
+/// guaranteed to be stable) and their generic argument
 macro_rules! container_derive_impl {
-    ($t:ty) => {
+    // pointers to possibly unsized types, such as `Box<[T]>`
+    (?Sized: $($t:ty => $name:literal),* $(,)?) => {
+        $(
+            impl<T: Freezable + ?Sized> Freezable for $t {
+                const FREEZE_V1: u64 = crate::hash::v1::container($name, &[T::FREEZE_V1]);
+
+                fn schema_with_context(ctx: &mut crate::FreezeCtx ) -> crate::Schema {
+                    crate::Schema::Container {
+                        name: $name.into(),
+                        args: ::alloc::vec![T::schema_with_context(ctx)],
+                    }
+                }
+            }
+        )*
+    };
+    ($t:ty => $name:literal) => {
 
         impl<T: Freezable> Freezable for $t {
-            fn freeze_with_context(ctx: &mut crate::FreezeCtx ) -> u64 {
-                use core::hash::{Hash, Hasher};
-                #[allow(deprecated)]
-                let mut h = core::hash::SipHasher::new();
-                let name = stringify!($t);
-                name[..name.find('<').unwrap()].hash(&mut h);
-                T::freeze_with_context(ctx).hash(&mut h);
-                h.finish()
+            const FREEZE_V1: u64 = crate::hash::v1::container($name, &[T::FREEZE_V1]);
+
+            fn schema_with_context(ctx: &mut crate::FreezeCtx ) -> crate::Schema {
+                crate::Schema::Container {
+                    name: $name.into(),
+                    args: ::alloc::vec![T::schema_with_context(ctx)],
+                }
             }
         }
     };
-    ($($t:ty),*) => {
-        $(container_derive_impl!($t);)*
+    ($($t:ty => $name:literal),* $(,)?) => {
+        $(container_derive_impl!($t => $name);)*
     }
 }
 
 macro_rules! generic_derive_impl_no_inner_bound {
-    ($t:ty) => {
+    ($t:ty => $name:literal) => {
         impl<T> Freezable for $t {
-            fn freeze_with_context(_ctx: &mut crate::FreezeCtx ) -> u64 {
-                use core::hash::{Hash, Hasher};
-                #[allow(deprecated)]
-                let mut h = core::hash::SipHasher::new();
-                let name = stringify!($t);
-                name[..name.find('<').unwrap()].hash(&mut h);
-                h.finish()
+            const FREEZE_V1: u64 = crate::hash::v1::container($name, &[]);
+
+            fn schema_with_context(_ctx: &mut crate::FreezeCtx ) -> crate::Schema {
+                crate::Schema::Container {
+                    name: $name.into(),
+                    args: ::alloc::vec::Vec::new(),
+                }
             }
         }
     };
-    ($($t:ty),*) => {
-        $(generic_derive_impl_no_inner_bound!($t);)*
+    ($($t:ty => $name:literal),* $(,)?) => {
+        $(generic_derive_impl_no_inner_bound!($t => $name);)*
     }
 }
 
+/// types frozen by their name, which is explicit rather than
+/// `core::any::type_name` (that may change between compiler versions)
 macro_rules! assume_frozen {
-    ($t:ty) => {
+    ($t:ty => $name:literal) => {
 
         impl Freezable for $t {
-            fn freeze_with_context(_ctx: &mut crate::FreezeCtx ) -> u64 {
-                use core::hash::{Hash, Hasher};
-                #[allow(deprecated)]
-                let mut h = core::hash::SipHasher::new();
-                core::any::type_name::<$t>().hash(&mut h); // stable enough
-                h.finish()
+            const FREEZE_V1: u64 = crate::hash::v1::opaque($name);
+
+            fn schema_with_context(_ctx: &mut crate::FreezeCtx ) -> crate::Schema {
+                crate::Schema::Opaque($name.into())
             }
         }
     };
-    ($($t:ty),*) => {
-        $(assume_frozen!($t);)*
+    ($($t:ty => $name:literal),* $(,)?) => {
+        $(assume_frozen!($t => $name);)*
     }
 }
 pub(crate) use assume_frozen;
 pub(crate) use container_derive_impl;
 pub(crate) use generic_derive_impl_no_inner_bound;
+
+/// Implements `Freezable` for the trait objects of a trait declared in the
+/// calling crate: `dyn Trait<..>`, `dyn Trait<..> + Send`, `+ Sync` and
+/// `+ Send + Sync`. They are frozen as the given (stable) name of the trait,
+/// their auto traits and the types of their generic arguments and associated
+/// types (e.g `Item = T`), that must be `Freezable`. Trait objects with any
+/// lifetime are covered (`dyn Trait + 'a`), but not higher-ranked ones (e.g
+/// `dyn for<'a> Handler<&'a str>`), that can be frozen `#[frozone(as = ..)]`
+/// their `'static` version
+///
+/// ```
+/// use frozone::Freezable;
+///
+/// pub trait Stream {
+///     type Item;
+/// }
+/// frozone::freezable_dyn!(Stream => "my_crate::Stream", type Item);
+///
+/// pub trait Handler<Event> {}
+/// frozone::freezable_dyn!(Handler<Event> => "my_crate::Handler");
+///
+/// #[derive(Freezable)]
+/// struct Task {
+///     stream: Box<dyn Stream<Item = u8> + Send>,
+///     handler: Option<Box<dyn Handler<String>>>,
+/// }
+/// ```
+#[macro_export]
+macro_rules! freezable_dyn {
+    (
+        $trait:ident $(<$($generic:ident),+ $(,)?>)? => $name:literal
+        $(, type $assoc:ident)* $(,)?
+    ) => {
+        $crate::freezable_dyn!(@impl [] $trait [$($($generic)+)?] [$($assoc)*] $name);
+        $crate::freezable_dyn!(@impl [Send] $trait [$($($generic)+)?] [$($assoc)*] $name);
+        $crate::freezable_dyn!(@impl [Sync] $trait [$($($generic)+)?] [$($assoc)*] $name);
+        $crate::freezable_dyn!(@impl [Send Sync] $trait [$($($generic)+)?] [$($assoc)*] $name);
+    };
+    (
+        @impl [$($auto:ident)*] $trait:ident [$($generic:ident)*] [$($assoc:ident)*]
+        $name:literal
+    ) => {
+        impl<'a, $($generic: $crate::Freezable,)* $($assoc: $crate::Freezable,)*> $crate::Freezable
+            for dyn $trait<$($generic,)* $($assoc = $assoc,)*> + 'a $(+ ::core::marker::$auto)*
+        {
+            const FREEZE_V1: u64 = $crate::hash::v1::container(
+                $crate::freezable_dyn!(@name $name [$($generic)*] [$($assoc)*] [$($auto)*]),
+                &[
+                    $(<$generic as $crate::Freezable>::FREEZE_V1,)*
+                    $(<$assoc as $crate::Freezable>::FREEZE_V1,)*
+                ],
+            );
+
+            fn schema_with_context(ctx: &mut $crate::FreezeCtx) -> $crate::Schema {
+                $crate::Schema::Container {
+                    name: $crate::freezable_dyn!(
+                        @name $name [$($generic)*] [$($assoc)*] [$($auto)*]
+                    )
+                    .into(),
+                    args: [
+                        $(<$generic as $crate::Freezable>::schema_with_context(ctx),)*
+                        $(<$assoc as $crate::Freezable>::schema_with_context(ctx),)*
+                    ].into(),
+                }
+            }
+        }
+    };
+    // e.g `dyn my_crate::Handler<_, Item = _> + Send`
+    (@name $name:literal [] [] [$($auto:ident)*]) => {
+        concat!("dyn ", $name $(, " + ", stringify!($auto))*)
+    };
+    (@name $name:literal [$($generic:ident)*] [$($assoc:ident)*] [$($auto:ident)*]) => {
+        concat!(
+            "dyn ",
+            $name,
+            "<",
+            $crate::freezable_dyn!(@args [$($generic)*] [$($assoc)*]),
+            ">"
+            $(, " + ", stringify!($auto))*
+        )
+    };
+    (@args [$first:ident $($generic:ident)*] [$($assoc:ident)*]) => {
+        concat!(
+            "_"
+            $(, ", _", $crate::freezable_dyn!(@discard $generic))*
+            $(, ", ", stringify!($assoc), " = _")*
+        )
+    };
+    (@args [] [$first:ident $($assoc:ident)*]) => {
+        concat!(stringify!($first), " = _" $(, ", ", stringify!($assoc), " = _")*)
+    };
+    (@discard $generic:ident) => {
+        ""
+    };
+}
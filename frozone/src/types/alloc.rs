@@ -1,49 +1,55 @@
 use crate::{
-    Freezable, FreezeCtx,
+    Freezable, FreezeCtx, Schema,
+    hash::v1,
     types::{assume_frozen, container_derive_impl},
 };
-extern crate alloc;
 
 use alloc::alloc::Layout;
-use alloc::borrow::Cow;
+use alloc::borrow::{Cow, ToOwned};
 use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap, VecDeque};
 use alloc::ffi::CString;
 use alloc::string::String;
+use alloc::vec;
 use alloc::vec::Vec;
 
-assume_frozen!(String, CString, Layout);
+assume_frozen!(
+    String => "alloc::string::String",
+    CString => "alloc::ffi::c_str::CString",
+    Layout => "core::alloc::layout::Layout",
+);
 
 container_derive_impl!(
-    Box<T>,
-    Vec<T>,
-    BTreeSet<T>,
-    BinaryHeap<T>,
-    VecDeque<T>,
-    alloc::rc::Rc<T>,
-    alloc::rc::Weak<T>,
-    alloc::sync::Arc<T>,
-    alloc::sync::Weak<T>
+    Vec<T> => "Vec",
+    BTreeSet<T> => "BTreeSet",
+    BinaryHeap<T> => "BinaryHeap",
+    VecDeque<T> => "VecDeque",
+);
+container_derive_impl!(?Sized:
+    Box<T> => "Box",
+    alloc::rc::Rc<T> => "alloc::rc::Rc",
+    alloc::rc::Weak<T> => "alloc::rc::Weak",
+    alloc::sync::Arc<T> => "alloc::sync::Arc",
+    alloc::sync::Weak<T> => "alloc::sync::Weak",
 );
 
 impl<T: Freezable, E: Freezable> Freezable for BTreeMap<T, E> {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "BTreeMap".hash(&mut h);
-        T::freeze_with_context(ctx).hash(&mut h);
-        E::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::container("BTreeMap", &[T::FREEZE_V1, E::FREEZE_V1]);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "BTreeMap".into(),
+            args: vec![T::schema_with_context(ctx), E::schema_with_context(ctx)],
+        }
     }
 }
-impl<T: Freezable + Clone> Freezable for Cow<'_, T> {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "Cow".hash(&mut h);
-        T::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+impl<T: Freezable + ToOwned + ?Sized> Freezable for Cow<'_, T> {
+    const FREEZE_V1: u64 = v1::container("Cow", &[T::FREEZE_V1]);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "Cow".into(),
+            args: vec![T::schema_with_context(ctx)],
+        }
     }
 }
@@ -1,7 +1,9 @@
 use crate::{
-    Freezable, FreezeCtx,
+    Freezable, FreezeCtx, Schema,
+    hash::v1,
     types::{assume_frozen, container_derive_impl, generic_derive_impl_no_inner_bound},
 };
+use alloc::vec;
 use core::any::TypeId;
 use core::cell::{Cell, LazyCell, OnceCell, Ref, RefCell, RefMut, UnsafeCell};
 use core::cmp::{Ordering, Reverse};
@@ -24,81 +26,60 @@ use core::sync::atomic::*;
 
 // even if one could argue all phantomdata<T> resolve to the same actual 'type'
 container_derive_impl!(
-    Option<T>,
-    Pin<T>,
-    MaybeUninit<T>,
-    PhantomData<T>,
-    Discriminant<T>,
-    ManuallyDrop<T>
+    Option<T> => "Option",
+    Pin<T> => "Pin",
+    MaybeUninit<T> => "MaybeUninit",
+    PhantomData<T> => "PhantomData",
+    Discriminant<T> => "Discriminant",
+    ManuallyDrop<T> => "ManuallyDrop",
 );
 
 impl<T: Freezable, E: Freezable> Freezable for Result<T, E> {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "Result".hash(&mut h); // prevent collisions with other containers types
-        T::freeze_with_context(ctx).hash(&mut h);
-        E::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::container("Result", &[T::FREEZE_V1, E::FREEZE_V1]);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "Result".into(),
+            args: vec![T::schema_with_context(ctx), E::schema_with_context(ctx)],
+        }
     }
 }
 
-assume_frozen!(TypeId, Ordering, PhantomPinned);
-container_derive_impl!(Reverse<T>, Pending<T>, Ready<T>);
+assume_frozen!(
+    TypeId => "core::any::TypeId",
+    Ordering => "core::cmp::Ordering",
+    PhantomPinned => "core::marker::PhantomPinned",
+);
+container_derive_impl!(Reverse<T> => "Reverse", Pending<T> => "Pending", Ready<T> => "Ready");
 
 assume_frozen!(
-    NonZeroI8,
-    NonZeroI16,
-    NonZeroI32,
-    NonZeroI64,
-    NonZeroI128,
-    NonZeroIsize,
-    NonZeroU8,
-    NonZeroU16,
-    NonZeroU32,
-    NonZeroU64,
-    NonZeroU128,
-    NonZeroUsize,
-    FpCategory
+    NonZeroI8 => "core::num::nonzero::NonZero<i8>",
+    NonZeroI16 => "core::num::nonzero::NonZero<i16>",
+    NonZeroI32 => "core::num::nonzero::NonZero<i32>",
+    NonZeroI64 => "core::num::nonzero::NonZero<i64>",
+    NonZeroI128 => "core::num::nonzero::NonZero<i128>",
+    NonZeroIsize => "core::num::nonzero::NonZero<isize>",
+    NonZeroU8 => "core::num::nonzero::NonZero<u8>",
+    NonZeroU16 => "core::num::nonzero::NonZero<u16>",
+    NonZeroU32 => "core::num::nonzero::NonZero<u32>",
+    NonZeroU64 => "core::num::nonzero::NonZero<u64>",
+    NonZeroU128 => "core::num::nonzero::NonZero<u128>",
+    NonZeroUsize => "core::num::nonzero::NonZero<usize>",
+    FpCategory => "core::num::FpCategory",
 );
 
 container_derive_impl!(
-    NonNull<T>,
-    Wrapping<T>,
-    Cell<T>,
-    RefCell<T>,
-    UnsafeCell<T>,
-    Ref<'_, T>,
-    RefMut<'_, T>,
-    LazyCell<T>,
-    OnceCell<T>
+    NonNull<T> => "NonNull",
+    Wrapping<T> => "Wrapping",
+    Cell<T> => "Cell",
+    RefCell<T> => "RefCell",
+    UnsafeCell<T> => "UnsafeCell",
+    Ref<'_, T> => "Ref",
+    RefMut<'_, T> => "RefMut",
+    LazyCell<T> => "LazyCell",
+    OnceCell<T> => "OnceCell",
 );
 
-impl<T: Freezable> Freezable for (T,) {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "(,)".hash(&mut h); // prevent collisions with other container types
-        1.hash(&mut h); // add tuple len to hash
-        T::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
-    }
-}
-impl<T: Freezable, U: Freezable> Freezable for (T, U) {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "(,)".hash(&mut h); // prevent collisions with other container types
-        2.hash(&mut h); // add tuple len to hash
-        T::freeze_with_context(ctx).hash(&mut h);
-        U::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
-    }
-}
-
 #[macro_export]
 macro_rules! tuple_derive_impl {
     ($($ty:ident),*) => {
@@ -106,21 +87,18 @@ macro_rules! tuple_derive_impl {
         where
             $($ty: Freezable),*
         {
+            const FREEZE_V1: u64 = $crate::hash::v1::tuple(&[$($ty::FREEZE_V1,)*]);
 
-            fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-                use core::hash::{Hash, Hasher};
-                #[allow(deprecated)]
-                let mut h = core::hash::SipHasher::new();
-                "(,)".hash(&mut h); // prevent collisions with other container types
-                [($($ty::freeze_with_context(ctx),)*)].len().hash(&mut h); // small hack to add tuple len to hash
-                ($($ty::freeze_with_context(ctx).hash(&mut h),)*);
-                h.finish()
+            fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+                Schema::Tuple(vec![$($ty::schema_with_context(ctx),)*])
             }
         }
     };
 }
 
-// macro-generated impls for tuple of size 3 -> 13
+// macro-generated impls for tuple of size 1 -> 13
+tuple_derive_impl!(A);
+tuple_derive_impl!(A, B);
 tuple_derive_impl!(A, B, C);
 tuple_derive_impl!(A, B, C, D);
 tuple_derive_impl!(A, B, C, D, E);
@@ -133,135 +111,197 @@ tuple_derive_impl!(A, B, C, D, E, F, G, H, I, J, K);
 tuple_derive_impl!(A, B, C, D, E, F, G, H, I, J, K, L);
 tuple_derive_impl!(A, B, C, D, E, F, G, H, I, J, K, L, M);
 
+/// fn pointers, frozen as their ABI and safety (in the container name) and
+/// their argument types followed by their return type, and likewise
+/// `dyn Fn*` trait objects (as the trait and its auto traits)
+macro_rules! fn_derive_impl {
+    ($($ty:ident),*) => {
+        fn_derive_impl!("fn" => [] fn($($ty),*) -> R; $($ty),*);
+        fn_derive_impl!("unsafe fn" => [] unsafe fn($($ty),*) -> R; $($ty),*);
+        fn_derive_impl!("extern \"C\" fn" => [] extern "C" fn($($ty),*) -> R; $($ty),*);
+        fn_derive_impl!(
+            "unsafe extern \"C\" fn" => [] unsafe extern "C" fn($($ty),*) -> R; $($ty),*
+        );
+        fn_derive_impl!(dyn Fn; $($ty),*);
+        fn_derive_impl!(dyn FnMut; $($ty),*);
+        fn_derive_impl!(dyn FnOnce; $($ty),*);
+    };
+    (dyn $trait:ident; $($ty:ident),*) => {
+        fn_derive_impl!(dyn $trait []; $($ty),*);
+        fn_derive_impl!(dyn $trait [Send]; $($ty),*);
+        fn_derive_impl!(dyn $trait [Sync]; $($ty),*);
+        fn_derive_impl!(dyn $trait [Send Sync]; $($ty),*);
+    };
+    // whatever their lifetime, e.g `&'a (dyn Fn() + 'a)`
+    (dyn $trait:ident [$($auto:ident)*]; $($ty:ident),*) => {
+        fn_derive_impl!(
+            concat!("dyn ", stringify!($trait) $(, " + ", stringify!($auto))*)
+                => ['a] dyn $trait($($ty),*) -> R + 'a $(+ $auto)*;
+            $($ty),*
+        );
+    };
+    ($name:expr => [$($lt:lifetime)?] $fn:ty; $($ty:ident),*) => {
+        impl<$($lt,)? $($ty: Freezable,)* R: Freezable> Freezable for $fn {
+            const FREEZE_V1: u64 = v1::container($name, &[$($ty::FREEZE_V1,)* R::FREEZE_V1]);
+
+            fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+                Schema::Container {
+                    name: $name.into(),
+                    args: vec![$($ty::schema_with_context(ctx),)* R::schema_with_context(ctx)],
+                }
+            }
+        }
+    };
+}
+
+// fn pointers and `dyn Fn*` with 0 -> 12 arguments (without higher-ranked lifetimes: the
+// impls cover `fn(&'static str)`, not `fn(&str)`)
+fn_derive_impl!();
+fn_derive_impl!(A);
+fn_derive_impl!(A, B);
+fn_derive_impl!(A, B, C);
+fn_derive_impl!(A, B, C, D);
+fn_derive_impl!(A, B, C, D, E);
+fn_derive_impl!(A, B, C, D, E, F);
+fn_derive_impl!(A, B, C, D, E, F, G);
+fn_derive_impl!(A, B, C, D, E, F, G, H);
+fn_derive_impl!(A, B, C, D, E, F, G, H, I);
+fn_derive_impl!(A, B, C, D, E, F, G, H, I, J);
+fn_derive_impl!(A, B, C, D, E, F, G, H, I, J, K);
+fn_derive_impl!(A, B, C, D, E, F, G, H, I, J, K, L);
+
 // slice & iterator-related types (only the simplests and most common)
 container_derive_impl!(
-    core::slice::Iter<'_, T>,
-    core::slice::IterMut<'_, T>,
-    core::slice::ChunksMut<'_, T>,
-    core::slice::Chunks<'_, T>,
-    core::slice::Windows<'_, T>
+    core::slice::Iter<'_, T> => "core::slice::Iter",
+    core::slice::IterMut<'_, T> => "core::slice::IterMut",
+    core::slice::ChunksMut<'_, T> => "core::slice::ChunksMut",
+    core::slice::Chunks<'_, T> => "core::slice::Chunks",
+    core::slice::Windows<'_, T> => "core::slice::Windows",
 );
 container_derive_impl!(
-    Cloned<T>,
-    Copied<T>,
-    Empty<T>,
-    Enumerate<T>,
-    Rev<T>,
-    Take<T>,
-    Skip<T>,
-    StepBy<T>,
-    Fuse<T>,
-    Cycle<T>
+    Cloned<T> => "Cloned",
+    Copied<T> => "Copied",
+    Empty<T> => "Empty",
+    Enumerate<T> => "Enumerate",
+    Rev<T> => "Rev",
+    Take<T> => "Take",
+    Skip<T> => "Skip",
+    StepBy<T> => "StepBy",
+    Fuse<T> => "Fuse",
+    Cycle<T> => "Cycle",
 );
 // Flatten and Once have additional requirements, so they're not included
 impl<T: Freezable, E: Freezable> Freezable for Chain<T, E> {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "Chain".hash(&mut h);
-        T::freeze_with_context(ctx).hash(&mut h);
-        E::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::container("Chain", &[T::FREEZE_V1, E::FREEZE_V1]);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "Chain".into(),
+            args: vec![T::schema_with_context(ctx), E::schema_with_context(ctx)],
+        }
     }
 }
 
 impl<T: Freezable, E: Freezable> Freezable for Filter<T, E> {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "Filter".hash(&mut h);
-        T::freeze_with_context(ctx).hash(&mut h);
-        E::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::container("Filter", &[T::FREEZE_V1, E::FREEZE_V1]);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "Filter".into(),
+            args: vec![T::schema_with_context(ctx), E::schema_with_context(ctx)],
+        }
     }
 }
 
 impl<T: Freezable, E: Freezable> Freezable for Inspect<T, E> {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "Inspect".hash(&mut h);
-        T::freeze_with_context(ctx).hash(&mut h);
-        E::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::container("Inspect", &[T::FREEZE_V1, E::FREEZE_V1]);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "Inspect".into(),
+            args: vec![T::schema_with_context(ctx), E::schema_with_context(ctx)],
+        }
     }
 }
 
 impl<T: Freezable, E: Freezable> Freezable for Map<T, E> {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "Map".hash(&mut h);
-        T::freeze_with_context(ctx).hash(&mut h);
-        E::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::container("Map", &[T::FREEZE_V1, E::FREEZE_V1]);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "Map".into(),
+            args: vec![T::schema_with_context(ctx), E::schema_with_context(ctx)],
+        }
     }
 }
 
 impl<T: Freezable, E: Freezable> Freezable for Zip<T, E> {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "Zip".hash(&mut h);
-        T::freeze_with_context(ctx).hash(&mut h);
-        E::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::container("Zip", &[T::FREEZE_V1, E::FREEZE_V1]);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "Zip".into(),
+            args: vec![T::schema_with_context(ctx), E::schema_with_context(ctx)],
+        }
     }
 }
 
 //core::net types
 assume_frozen!(
-    IpAddr,
-    Ipv4Addr,
-    Ipv6Addr,
-    SocketAddr,
-    SocketAddrV4,
-    SocketAddrV6
+    IpAddr => "core::net::ip_addr::IpAddr",
+    Ipv4Addr => "core::net::ip_addr::Ipv4Addr",
+    Ipv6Addr => "core::net::ip_addr::Ipv6Addr",
+    SocketAddr => "core::net::socket_addr::SocketAddr",
+    SocketAddrV4 => "core::net::socket_addr::SocketAddrV4",
+    SocketAddrV6 => "core::net::socket_addr::SocketAddrV6",
 );
 
 //core::ops
-assume_frozen!(RangeFull);
-container_derive_impl!(Bound<T>);
+assume_frozen!(RangeFull => "core::ops::range::RangeFull");
+container_derive_impl!(Bound<T> => "Bound");
 generic_derive_impl_no_inner_bound!(
-    Range<T>,
-    RangeFrom<T>,
-    RangeTo<T>,
-    RangeInclusive<T>,
-    RangeToInclusive<T>
+    Range<T> => "Range",
+    RangeFrom<T> => "RangeFrom",
+    RangeTo<T> => "RangeTo",
+    RangeInclusive<T> => "RangeInclusive",
+    RangeToInclusive<T> => "RangeToInclusive",
 );
 
-assume_frozen!(Location<'_>, PanicInfo<'_>);
+assume_frozen!(
+    Location<'_> => "core::panic::location::Location<'_>",
+    PanicInfo<'_> => "core::panic::panic_info::PanicInfo<'_>",
+);
 
 // core::str
-assume_frozen!(Bytes<'_>, Chars<'_>, CharIndices<'_>, Lines<'_>);
+assume_frozen!(
+    Bytes<'_> => "core::str::iter::Bytes<'_>",
+    Chars<'_> => "core::str::iter::Chars<'_>",
+    CharIndices<'_> => "core::str::iter::CharIndices<'_>",
+    Lines<'_> => "core::str::iter::Lines<'_>",
+);
 
 // atomics
 assume_frozen!(
-    AtomicBool,
-    AtomicI8,
-    AtomicI16,
-    AtomicI32,
-    AtomicI64,
-    AtomicIsize,
-    AtomicU8,
-    AtomicU16,
-    AtomicU32,
-    AtomicU64,
-    AtomicUsize,
-    core::sync::atomic::Ordering
+    AtomicBool => "core::sync::atomic::AtomicBool",
+    AtomicI8 => "core::sync::atomic::AtomicI8",
+    AtomicI16 => "core::sync::atomic::AtomicI16",
+    AtomicI32 => "core::sync::atomic::AtomicI32",
+    AtomicI64 => "core::sync::atomic::AtomicI64",
+    AtomicIsize => "core::sync::atomic::AtomicIsize",
+    AtomicU8 => "core::sync::atomic::AtomicU8",
+    AtomicU16 => "core::sync::atomic::AtomicU16",
+    AtomicU32 => "core::sync::atomic::AtomicU32",
+    AtomicU64 => "core::sync::atomic::AtomicU64",
+    AtomicUsize => "core::sync::atomic::AtomicUsize",
+    core::sync::atomic::Ordering => "core::sync::atomic::Ordering",
 );
-container_derive_impl!(AtomicPtr<T>);
+container_derive_impl!(AtomicPtr<T> => "AtomicPtr");
 
 // future & time
-container_derive_impl!(core::task::Poll<T>);
+container_derive_impl!(core::task::Poll<T> => "core::task::Poll");
 assume_frozen!(
-    core::time::Duration,
-    core::task::RawWaker,
-    core::task::Waker,
-    core::task::Context<'_>
+    core::time::Duration => "core::time::Duration",
+    core::task::RawWaker => "core::task::wake::RawWaker",
+    core::task::Waker => "core::task::wake::Waker",
+    core::task::Context<'_> => "core::task::wake::Context<'_>",
 );
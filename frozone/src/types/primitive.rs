@@ -1,103 +1,102 @@
-use crate::{Freezable, FreezeCtx, types::assume_frozen};
+use crate::{Freezable, FreezeCtx, Schema, hash::v1, types::assume_frozen};
+use alloc::boxed::Box;
+use alloc::vec;
 use core::ffi::{CStr, c_void};
 
-assume_frozen!(CStr, c_void);
+assume_frozen!(CStr => "core::ffi::c_str::CStr", c_void => "core::ffi::c_void");
 
 assume_frozen!(
-    i8,
-    i16,
-    i32,
-    i64,
-    i128,
-    isize,
-    u8,
-    u16,
-    u32,
-    u64,
-    u128,
-    usize,
-    bool,
-    char,
-    str,
-    ()
+    i8 => "i8",
+    i16 => "i16",
+    i32 => "i32",
+    i64 => "i64",
+    i128 => "i128",
+    isize => "isize",
+    u8 => "u8",
+    u16 => "u16",
+    u32 => "u32",
+    u64 => "u64",
+    u128 => "u128",
+    usize => "usize",
+    bool => "bool",
+    char => "char",
+    str => "str",
+    () => "()",
 );
-assume_frozen!(f32, f64);
+assume_frozen!(f32 => "f32", f64 => "f64");
 
 impl<T: Freezable, const N: usize> Freezable for [T; N] {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "[;N]".hash(&mut h);
-        N.hash(&mut h);
-        T::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::array(N, T::FREEZE_V1);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Array {
+            len: N,
+            item: Box::new(T::schema_with_context(ctx)),
+        }
     }
 }
 
 impl<T: Freezable> Freezable for [T] {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "[]".hash(&mut h);
-        T::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::container("[]", &[T::FREEZE_V1]);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "[]".into(),
+            args: vec![T::schema_with_context(ctx)],
+        }
     }
 }
 
 impl<T: Freezable> Freezable for &T {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "&".hash(&mut h);
-        T::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::container("&", &[T::FREEZE_V1]);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "&".into(),
+            args: vec![T::schema_with_context(ctx)],
+        }
     }
 }
 
 // mut and const ptr evaluate to the same freeze hash
 impl<T: Freezable> Freezable for *const T {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "*const".hash(&mut h);
-        T::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::container("*const", &[T::FREEZE_V1]);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "*const".into(),
+            args: vec![T::schema_with_context(ctx)],
+        }
     }
 }
 
 // mut and const ptr evaluate to the same freeze hash
 impl<T: Freezable> Freezable for *mut T {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "*mut".hash(&mut h);
-        T::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::container("*mut", &[T::FREEZE_V1]);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "*mut".into(),
+            args: vec![T::schema_with_context(ctx)],
+        }
     }
 }
 
 impl<T: Freezable> Freezable for &[T] {
-    fn freeze_with_context(ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "&[]".hash(&mut h);
-        T::freeze_with_context(ctx).hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::container("&[]", &[T::FREEZE_V1]);
+
+    fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "&[]".into(),
+            args: vec![T::schema_with_context(ctx)],
+        }
     }
 }
 
 impl Freezable for &str {
-    fn freeze_with_context(_ctx: &mut FreezeCtx) -> u64 {
-        use core::hash::{Hash, Hasher};
-        #[allow(deprecated)]
-        let mut h = core::hash::SipHasher::new();
-        "&str".hash(&mut h);
-        h.finish()
+    const FREEZE_V1: u64 = v1::opaque("&str");
+
+    fn schema_with_context(_ctx: &mut FreezeCtx) -> Schema {
+        Schema::Opaque("&str".into())
     }
 }
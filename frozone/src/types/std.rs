@@ -20,61 +20,61 @@ use std::thread::{Builder, JoinHandle, LocalKey, Thread, ThreadId};
 use std::time::{Instant, SystemTime};
 
 container_derive_impl!(
-    Mutex<T>,
-    MutexGuard<'_, T>,
-    RwLock<T>,
-    RwLockReadGuard<'_, T>,
-    RwLockWriteGuard<'_, T>,
-    JoinHandle<T>,
-    LocalKey<T>
+    Mutex<T> => "Mutex",
+    MutexGuard<'_, T> => "MutexGuard",
+    RwLock<T> => "RwLock",
+    RwLockReadGuard<'_, T> => "RwLockReadGuard",
+    RwLockWriteGuard<'_, T> => "RwLockWriteGuard",
+    JoinHandle<T> => "JoinHandle",
+    LocalKey<T> => "LocalKey",
 );
 
 assume_frozen!(
-    OsStr,
-    OsString,
-    DirBuilder,
-    DirEntry,
-    File,
-    FileType,
-    Metadata,
-    OpenOptions,
-    Permissions,
-    ReadDir,
-    Error,
-    ErrorKind,
-    Stdin,
-    Stderr,
-    Stdout,
-    Repeat,
-    Sink,
-    TcpListener,
-    TcpStream,
-    UdpSocket,
-    Incoming<'_>,
-    Path,
-    PathBuf,
-    Component<'_>,
-    Iter<'_>,
-    Prefix<'_>,
-    PrefixComponent<'_>,
-    ChildStdout,
-    ChildStderr,
-    ChildStdin,
-    Child,
-    Command,
-    ExitStatus,
-    ExitCode,
-    Stdio,
-    Output,
-    Barrier,
-    BarrierWaitResult,
-    Condvar,
-    Once,
-    OnceState,
-    WaitTimeoutResult,
-    Builder,
-    Thread,
-    ThreadId,
-    SystemTime,
-    Instant
+    OsStr => "std::ffi::os_str::OsStr",
+    OsString => "std::ffi::os_str::OsString",
+    DirBuilder => "std::fs::DirBuilder",
+    DirEntry => "std::fs::DirEntry",
+    File => "std::fs::File",
+    FileType => "std::fs::FileType",
+    Metadata => "std::fs::Metadata",
+    OpenOptions => "std::fs::OpenOptions",
+    Permissions => "std::fs::Permissions",
+    ReadDir => "std::fs::ReadDir",
+    Error => "std::io::error::Error",
+    ErrorKind => "std::io::error::ErrorKind",
+    Stdin => "std::io::stdio::Stdin",
+    Stderr => "std::io::stdio::Stderr",
+    Stdout => "std::io::stdio::Stdout",
+    Repeat => "std::io::util::Repeat",
+    Sink => "std::io::util::Sink",
+    TcpListener => "std::net::tcp::TcpListener",
+    TcpStream => "std::net::tcp::TcpStream",
+    UdpSocket => "std::net::udp::UdpSocket",
+    Incoming<'_> => "std::net::tcp::Incoming<'_>",
+    Path => "std::path::Path",
+    PathBuf => "std::path::PathBuf",
+    Component<'_> => "std::path::Component<'_>",
+    Iter<'_> => "std::path::Iter<'_>",
+    Prefix<'_> => "std::path::Prefix<'_>",
+    PrefixComponent<'_> => "std::path::PrefixComponent<'_>",
+    ChildStdout => "std::process::ChildStdout",
+    ChildStderr => "std::process::ChildStderr",
+    ChildStdin => "std::process::ChildStdin",
+    Child => "std::process::Child",
+    Command => "std::process::Command",
+    ExitStatus => "std::process::ExitStatus",
+    ExitCode => "std::process::ExitCode",
+    Stdio => "std::process::Stdio",
+    Output => "std::process::Output",
+    Barrier => "std::sync::barrier::Barrier",
+    BarrierWaitResult => "std::sync::barrier::BarrierWaitResult",
+    Condvar => "std::sync::poison::condvar::Condvar",
+    Once => "std::sync::once::Once",
+    OnceState => "std::sync::once::OnceState",
+    WaitTimeoutResult => "std::sync::WaitTimeoutResult",
+    Builder => "std::thread::builder::Builder",
+    Thread => "std::thread::thread::Thread",
+    ThreadId => "std::thread::id::ThreadId",
+    SystemTime => "std::time::SystemTime",
+    Instant => "std::time::Instant",
 );
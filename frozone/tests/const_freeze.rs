@@ -0,0 +1,100 @@
+#![allow(unused)]
+use frozone::Freezable;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+#[derive(Freezable)]
+struct MyType {
+    field_a: u64,
+    field_b: Vec<(u8, [MySubType; 3])>,
+    field_c: Option<Result<&'static str, String>>,
+    #[assume_frozen]
+    field_d: std::fs::File,
+    #[assume_frozen(freeze_generics)]
+    field_e: std::vec::Vec<BTreeMap<u8, (u8, u16, u32)>>,
+}
+
+#[derive(Freezable)]
+#[repr(u8)]
+enum MySubType {
+    A = 4,
+    B(u8, String),
+    #[assume_frozen]
+    C {
+        a: u32,
+    },
+    #[assume_frozen(freeze_generics)]
+    D(Vec<u8>, Box<u16>),
+    E {
+        a: Cow<'static, u8>,
+    },
+}
+
+#[derive(Freezable)]
+struct MyTuple(u8, #[assume_frozen] u16, MySubType);
+
+#[derive(Freezable)]
+struct MyUnit;
+
+#[derive(Freezable)]
+struct MyGeneric<'a, T: Freezable> {
+    a: &'a [T],
+    b: [T; 3],
+}
+
+// compile-time check
+const _: () = assert!(MyType::FREEZE == 18386414664367925211);
+
+#[test]
+fn const_freeze() {
+    assert_eq!(MyType::FREEZE, MyType::freeze());
+    assert_eq!(MySubType::FREEZE, MySubType::freeze());
+    assert_eq!(MyTuple::FREEZE, MyTuple::freeze());
+    assert_eq!(MyUnit::FREEZE, MyUnit::freeze());
+    assert_eq!(
+        MyGeneric::<'static, u8>::FREEZE,
+        MyGeneric::<'static, u8>::freeze()
+    );
+    assert_ne!(
+        MyGeneric::<'static, u8>::FREEZE,
+        MyGeneric::<'static, u16>::FREEZE
+    );
+}
+
+#[test]
+fn const_freeze_builtins() {
+    assert_eq!(<()>::FREEZE, <()>::freeze());
+    assert_eq!(<&str>::FREEZE, <&str>::freeze());
+    assert_eq!(<[u8]>::FREEZE, <[u8]>::freeze());
+    assert_eq!(<*const u8>::FREEZE, <*const u8>::freeze());
+    assert_eq!(<(u8, i8, u16, i16)>::FREEZE, <(u8, i8, u16, i16)>::freeze());
+    assert_eq!(std::rc::Rc::<u8>::FREEZE, std::rc::Rc::<u8>::freeze());
+    assert_eq!(
+        std::ops::Range::<u8>::FREEZE,
+        std::ops::Range::<u8>::freeze()
+    );
+    assert_eq!(std::num::NonZeroU8::FREEZE, std::num::NonZeroU8::freeze());
+    assert_eq!(
+        core::cell::RefCell::<Option<u8>>::FREEZE,
+        core::cell::RefCell::<Option<u8>>::freeze()
+    );
+}
+
+#[test]
+fn const_freeze_recursive_unused() {
+    // type-recursive types compile as long as their FREEZE is not used
+    #[derive(Freezable)]
+    struct Cycle {
+        a: Option<Box<Cycle>>,
+    }
+    #[derive(Freezable)]
+    struct CycleSelf<T: Freezable> {
+        a: Vec<Self>,
+        b: T,
+    }
+    assert_eq!(Cycle::freeze(), Cycle::schema().freeze());
+    assert_eq!(
+        CycleSelf::<u8>::freeze(),
+        CycleSelf::<u8>::schema().freeze()
+    );
+}
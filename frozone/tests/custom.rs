@@ -0,0 +1,212 @@
+#![allow(unused)]
+use frozone::schema::{Field, Shape};
+use frozone::{Freezable, FreezeCtx, Schema};
+
+/// external type, that doesn't implement `Freezable`
+mod external {
+    pub struct Uuid(pub [u8; 16]);
+}
+
+/// the uuid is (de)serialized as a string
+fn uuid_schema(_ctx: &mut FreezeCtx) -> Schema {
+    Schema::Opaque("uuid::Uuid".into())
+}
+
+#[test]
+fn with() {
+    #[derive(Freezable)]
+    struct User {
+        #[frozone(with = uuid_schema)]
+        id: external::Uuid,
+        name: String,
+    }
+    #[derive(Freezable)]
+    struct Assumed {
+        #[assume_frozen]
+        id: external::Uuid,
+        name: String,
+    }
+    #[derive(Freezable)]
+    struct Bytes {
+        #[frozone(with = <[u8; 16] as Freezable>::schema_with_context)]
+        id: external::Uuid,
+        name: String,
+    }
+    let Schema::Struct { fields, .. } = User::schema() else {
+        panic!("not a struct");
+    };
+    assert_eq!(fields[0].schema, Schema::Opaque("uuid::Uuid".into()));
+    assert_eq!(User::freeze(), User::schema().freeze());
+    assert_ne!(User::freeze(), Assumed::freeze());
+    assert_ne!(User::freeze(), Bytes::freeze());
+    let Schema::Struct { fields, .. } = Bytes::schema() else {
+        panic!("not a struct");
+    };
+    assert_eq!(fields[0].schema, <[u8; 16]>::schema());
+
+    #[derive(Freezable)]
+    enum Event {
+        Created(#[frozone(with = uuid_schema)] external::Uuid),
+        Renamed {
+            #[frozone(with = uuid_schema)]
+            id: external::Uuid,
+            name: String,
+        },
+    }
+    let Schema::Enum { variants, .. } = Event::schema() else {
+        panic!("not an enum");
+    };
+    assert_eq!(
+        variants[0].fields[0].schema,
+        uuid_schema(&mut FreezeCtx::default())
+    );
+    assert_eq!(
+        variants[1].fields[0].schema,
+        uuid_schema(&mut FreezeCtx::default())
+    );
+}
+
+#[test]
+fn with_context() {
+    /// describes the external type with the schema of a mirror type
+    fn nested(ctx: &mut FreezeCtx) -> Schema {
+        Schema::Container {
+            name: "Vec".into(),
+            args: vec![<Outer as Freezable>::schema_with_context(ctx)],
+        }
+    }
+    #[derive(Freezable)]
+    struct Outer {
+        #[frozone(with = nested)]
+        children: external::Uuid,
+    }
+    // the context is shared: the cycle back to `Outer` is detected
+    let Schema::Struct { fields, .. } = Outer::schema() else {
+        panic!("not a struct");
+    };
+    assert_eq!(
+        fields[0].schema,
+        Schema::Container {
+            name: "Vec".into(),
+            args: vec![Schema::Recursion {
+                name: "Outer".into(),
+                depth: 1
+            }],
+        }
+    );
+}
+
+#[test]
+fn proxy() {
+    mod proxied {
+        use super::external;
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub struct User {
+            #[frozone(as = String)]
+            pub id: external::Uuid,
+            #[frozone(as = super::Mirror)]
+            pub key: external::Uuid,
+        }
+        #[derive(Freezable)]
+        pub enum Event {
+            Created(#[frozone(as = String)] external::Uuid),
+        }
+    }
+    mod plain {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub struct User {
+            pub id: String,
+            pub key: super::Mirror,
+        }
+        #[derive(Freezable)]
+        pub enum Event {
+            Created(String),
+        }
+    }
+    assert_eq!(proxied::User::schema(), plain::User::schema());
+    assert_eq!(proxied::User::freeze(), plain::User::freeze());
+    assert_eq!(proxied::User::freeze_v2(), plain::User::freeze_v2());
+    assert_eq!(proxied::Event::freeze(), plain::Event::freeze());
+    // the proxy's freeze is known at compile-time
+    assert_eq!(proxied::User::FREEZE, plain::User::FREEZE);
+}
+
+/// mirror of `external::Uuid`
+#[derive(Freezable)]
+struct Mirror([u8; 16]);
+
+/// foreign crate, whose types can't implement `Freezable`
+mod other {
+    pub struct Config {
+        pub name: String,
+        pub retries: u8,
+        pub id: super::external::Uuid,
+    }
+    pub enum Mode {
+        Fast,
+        Slow(u8),
+        Custom { level: u32 },
+    }
+    pub struct Wrapper<T>(pub T);
+}
+
+#[test]
+fn remote() {
+    #[derive(Freezable)]
+    #[frozone(remote = "other::Config")]
+    struct ConfigMirror {
+        name: String,
+        retries: u8,
+        #[frozone(as = String)]
+        id: external::Uuid,
+    }
+    #[derive(Freezable)]
+    #[frozone(remote = "other::Mode")]
+    enum ModeMirror {
+        Fast,
+        Slow(u8),
+        Custom { level: u32 },
+    }
+    #[derive(Freezable)]
+    #[frozone(remote = "other::Wrapper<T>")]
+    struct WrapperMirror<T: Freezable>(T);
+
+    #[derive(Freezable)]
+    struct Settings {
+        #[frozone(as = ConfigMirror)]
+        config: other::Config,
+        #[frozone(as = ModeMirror)]
+        mode: other::Mode,
+        #[frozone(as = WrapperMirror<u8>)]
+        wrapped: other::Wrapper<u8>,
+    }
+
+    // mirrors are named after the remote type
+    let Schema::Struct { name, fields, .. } = Settings::schema() else {
+        panic!("not a struct");
+    };
+    assert_eq!(fields[0].schema, ConfigMirror::schema());
+    assert!(matches!(
+        &fields[0].schema,
+        Schema::Struct { name, .. } if name == "Config"
+    ));
+    assert!(matches!(
+        &fields[1].schema,
+        Schema::Enum { name, .. } if name == "Mode"
+    ));
+    assert!(matches!(
+        &fields[2].schema,
+        Schema::Struct { name, .. } if name == "Wrapper"
+    ));
+
+    #[derive(Freezable)]
+    struct Config {
+        name: String,
+        retries: u8,
+        id: String,
+    }
+    assert_eq!(ConfigMirror::freeze(), Config::freeze());
+    assert_eq!(ConfigMirror::FREEZE, Config::FREEZE);
+}
@@ -0,0 +1,96 @@
+#![allow(unused)]
+use frozone::{Freezable, FreezeCtx, FreezeError, FreezeErrorKind, Schema, TYPE_RECURSION_LIMIT};
+
+#[derive(Freezable)]
+struct Nested<T: Freezable>(T);
+
+#[test]
+fn try_freeze() {
+    #[derive(Freezable)]
+    struct MyType {
+        a: Vec<u8>,
+    }
+    assert_eq!(MyType::try_freeze(), Ok(MyType::freeze()));
+    assert_eq!(MyType::try_schema(), Ok(MyType::schema()));
+}
+
+#[test]
+fn recursion_limit() {
+    #[derive(Freezable)]
+    struct Deep {
+        a: u8,
+        deep: Option<Nested<Nested<u16>>>,
+    }
+    assert!(Deep::try_freeze().is_ok());
+
+    // as if `Deep` was nested in 1022 other types
+    let mut ctx = frozone::internals::nested_ctx(TYPE_RECURSION_LIMIT as u32 - 2);
+    let schema = Deep::schema_with_context(&mut ctx);
+    let error = ctx.finish(schema).unwrap_err();
+    assert_eq!(error.kind, FreezeErrorKind::RecursionLimit);
+    assert_eq!(error.path, "Deep.deep.0");
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "could not freeze Deep.deep.0: exceeded the {} nested types limit",
+            TYPE_RECURSION_LIMIT
+        )
+    );
+    assert_eq!(
+        frozone::internals::TYPE_RECURSION_MESSAGE,
+        format!("exceeded the {} nested types limit", TYPE_RECURSION_LIMIT)
+    );
+}
+
+#[test]
+fn custom_error() {
+    /// a hand-written impl that refuses to be frozen
+    struct Unfreezable;
+    // (no compile-time freeze)
+    impl Freezable for Unfreezable {
+        fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+            ctx.fail(FreezeErrorKind::Unsupported("Unfreezable".into()));
+            Schema::AssumeFrozen
+        }
+    }
+
+    #[derive(Freezable)]
+    enum MyEnum {
+        A,
+        B { ok: u8, no: Vec<Unfreezable> },
+    }
+    assert_eq!(
+        MyEnum::try_schema(),
+        Err(FreezeError {
+            path: "MyEnum::B.no".into(),
+            kind: FreezeErrorKind::Unsupported("Unfreezable".into()),
+        })
+    );
+    assert_eq!(
+        Unfreezable::try_freeze().unwrap_err().to_string(),
+        "could not freeze: unsupported: Unfreezable"
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn freeze_panics() {
+    #[derive(Freezable)]
+    struct MyType {
+        a: Nested<Unsupported>,
+    }
+    struct Unsupported;
+    impl Freezable for Unsupported {
+        const FREEZE_V1: u64 = 0;
+
+        fn schema_with_context(ctx: &mut FreezeCtx) -> Schema {
+            ctx.fail(FreezeErrorKind::Policy("no".into()));
+            Schema::AssumeFrozen
+        }
+    }
+    let panic = std::panic::catch_unwind(MyType::freeze).unwrap_err();
+    assert_eq!(
+        panic.downcast_ref::<String>().unwrap(),
+        "could not freeze MyType.a.0: not allowed: no"
+    );
+}
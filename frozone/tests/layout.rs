@@ -0,0 +1,205 @@
+#![allow(unused)]
+use frozone::schema::{Layout, Profile};
+use frozone::{Freezable, Schema};
+
+fn layout<T: Freezable + ?Sized>() -> u64 {
+    T::freeze_with(Profile::Layout)
+}
+
+#[test]
+fn layout_schema() {
+    #[derive(Freezable)]
+    #[repr(C, align(8))]
+    struct Header {
+        tag: u8,
+        len: u32,
+    }
+    let Schema::Struct { layout, .. } = Header::schema() else {
+        panic!("not a struct");
+    };
+    assert_eq!(
+        layout,
+        Some(Layout {
+            repr: "C, align(8)".into(),
+            size: 8,
+            align: 8,
+            offsets: vec![0, 4],
+        })
+    );
+
+    #[derive(Freezable)]
+    #[repr(u16)]
+    enum Kind {
+        A = 1,
+        B(u8),
+    }
+    let Schema::Enum { layout, .. } = Kind::schema() else {
+        panic!("not an enum");
+    };
+    assert_eq!(
+        layout,
+        Some(Layout {
+            repr: "u16".into(),
+            size: 4,
+            align: 2,
+            offsets: vec![],
+        })
+    );
+
+    #[derive(Freezable)]
+    #[repr(C)]
+    struct Pair<T: Freezable>(T, u8);
+    let Schema::Struct { layout, .. } = Pair::<u16>::schema() else {
+        panic!("not a struct");
+    };
+    assert_eq!(layout.map(|l| l.offsets), Some(vec![0, 2]));
+
+    // nor do unsized types
+    #[derive(Freezable)]
+    #[repr(C)]
+    struct Packet {
+        len: u8,
+        data: [u8],
+    }
+    #[derive(Freezable)]
+    #[repr(C)]
+    struct Tail<T: Freezable + ?Sized>(u16, T);
+    assert!(matches!(
+        Packet::schema(),
+        Schema::Struct { layout: None, .. }
+    ));
+    assert!(matches!(
+        Tail::<str>::schema(),
+        Schema::Struct { layout: None, .. }
+    ));
+    // (whatever `T`, the impl being the same)
+    assert!(matches!(
+        Tail::<u8>::schema(),
+        Schema::Struct { layout: None, .. }
+    ));
+
+    // only `#[repr(...)]` types have a layout
+    #[derive(Freezable)]
+    struct Plain {
+        a: u8,
+    }
+    assert!(matches!(
+        Plain::schema(),
+        Schema::Struct { layout: None, .. }
+    ));
+}
+
+#[test]
+fn layout_profile() {
+    mod c {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        #[repr(C)]
+        pub struct Header {
+            pub tag: u8,
+            pub len: u32,
+        }
+        #[derive(Freezable)]
+        #[repr(u8)]
+        pub enum Kind {
+            A,
+            B,
+        }
+    }
+    mod packed {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        #[repr(C, packed)]
+        pub struct Header {
+            pub tag: u8,
+            pub len: u32,
+        }
+        #[derive(Freezable)]
+        #[repr(u32)]
+        pub enum Kind {
+            A,
+            B,
+        }
+    }
+    mod reordered {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        #[repr(C)]
+        pub struct Header {
+            pub len: u32,
+            pub tag: u8,
+        }
+        #[derive(Freezable)]
+        #[repr(u8)]
+        pub enum Kind {
+            B,
+            A,
+        }
+    }
+    // same schema, different layout
+    assert_eq!(c::Header::freeze_v2(), packed::Header::freeze_v2());
+    assert_ne!(layout::<c::Header>(), layout::<packed::Header>());
+    assert_eq!(c::Kind::freeze_v2(), packed::Kind::freeze_v2());
+    assert_ne!(layout::<c::Kind>(), layout::<packed::Kind>());
+
+    // the order of the fields and variants is frozen too
+    assert_eq!(c::Header::freeze_v2(), reordered::Header::freeze_v2());
+    assert_ne!(layout::<c::Header>(), layout::<reordered::Header>());
+    assert_ne!(layout::<c::Kind>(), layout::<reordered::Kind>());
+
+    // without a layout, the layout profile is the positional freeze
+    assert_eq!(layout::<Vec<(u8, u16)>>(), Vec::<(u8, u16)>::freeze_v2());
+    #[derive(Freezable)]
+    struct Plain {
+        a: u8,
+    }
+    assert_eq!(layout::<Plain>(), Plain::freeze_positional());
+    // other freezes ignore the layout
+    assert_ne!(c::Header::freeze_positional(), layout::<c::Header>());
+}
+
+#[test]
+fn layout_text_and_diff() {
+    #[derive(Freezable)]
+    #[repr(C)]
+    struct Header {
+        tag: u8,
+        len: u32,
+    }
+    let schema = Header::schema();
+    let text = schema.to_string();
+    assert!(text.contains("(layout \"C\" 8 4 0 4)"), "{}", text);
+    assert_eq!(text.parse::<Schema>(), Ok(schema.clone()));
+
+    let Schema::Struct {
+        name,
+        shape,
+        fields,
+        serde,
+        positional,
+        ..
+    } = schema.clone()
+    else {
+        panic!("not a struct");
+    };
+    let packed = Schema::Struct {
+        name,
+        shape,
+        fields,
+        serde,
+        positional,
+        layout: Some(Layout {
+            repr: "C, packed".into(),
+            size: 5,
+            align: 1,
+            offsets: vec![0, 1],
+        }),
+    };
+    let changes: Vec<_> = schema.diff(&packed).iter().map(|c| c.to_string()).collect();
+    assert_eq!(
+        changes,
+        [
+            "Header: #[repr(C)] size 8, align 4, offsets (0, 4) -> #[repr(C, packed)] size 5, align 1, offsets (0, 1)"
+        ]
+    );
+}
@@ -0,0 +1,326 @@
+#![cfg(feature = "std")]
+#![allow(unused)]
+use frozone::Freezable;
+use frozone::lock::{Algorithm, Lockfile};
+use frozone::schema::Profile;
+use std::path::PathBuf;
+
+/// fresh lockfile path, unique to the test
+fn lockfile_path(test: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("frozone-{}-{}.lock", test, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+mod v1 {
+    use frozone::Freezable;
+    #[derive(Freezable)]
+    pub struct Frozone {
+        pub power: Power,
+        pub equipment: Vec<Part>,
+    }
+    #[derive(Freezable)]
+    pub struct Power {
+        pub level: u32,
+    }
+    #[derive(Freezable)]
+    pub enum Part {
+        Sunglasses,
+        Skates,
+    }
+}
+
+mod v2 {
+    use frozone::Freezable;
+    #[derive(Freezable)]
+    pub struct Frozone {
+        pub power: Power,
+        pub equipment: Vec<Part>,
+    }
+    #[derive(Freezable)]
+    pub struct Power {
+        pub level: u64,
+    }
+    pub use super::v1::Part;
+}
+
+#[test]
+fn lock_update_and_check() {
+    let path = lockfile_path("update_and_check");
+
+    let mut lockfile = Lockfile::open(&path);
+    lockfile
+        .check::<v1::Frozone>("Frozone")
+        .check::<v1::Part>("Part");
+    assert_eq!(
+        lockfile.mismatches(),
+        [
+            "Frozone is not in the lockfile",
+            "Part is not in the lockfile"
+        ]
+    );
+    lockfile.update(true).finish();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.contains(&format!(
+        "[Frozone]\nalgorithm = v1\nfreeze = {}\n",
+        v1::Frozone::freeze()
+    )));
+    assert!(text.contains(&format!(
+        "[Part]\nalgorithm = v1\nfreeze = {}\n",
+        v1::Part::freeze()
+    )));
+
+    let mut lockfile = Lockfile::open(&path);
+    lockfile.update(false);
+    lockfile
+        .check::<v1::Frozone>("Frozone")
+        .check::<v1::Part>("Part");
+    assert!(lockfile.mismatches().is_empty());
+    lockfile.finish();
+
+    // entries of unchecked types are kept on update
+    Lockfile::open(&path)
+        .update(true)
+        .check::<v2::Frozone>("Frozone")
+        .finish();
+    let mut lockfile = Lockfile::open(&path);
+    lockfile.update(false);
+    lockfile
+        .check::<v2::Frozone>("Frozone")
+        .check::<v1::Part>("Part");
+    assert!(lockfile.mismatches().is_empty());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn lock_mismatch() {
+    let path = lockfile_path("mismatch");
+    Lockfile::open(&path)
+        .update(true)
+        .check::<v1::Frozone>("Frozone")
+        .finish();
+
+    let mut lockfile = Lockfile::open(&path);
+    lockfile.update(false).check::<v2::Frozone>("Frozone");
+    assert_eq!(
+        lockfile.mismatches(),
+        [format!(
+            "Frozone changed (freeze {} -> {})\n    Frozone.power.level: u32 -> u64",
+            v1::Frozone::freeze(),
+            v2::Frozone::freeze()
+        )]
+    );
+    let panic = std::panic::catch_unwind(move || lockfile.finish()).unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert!(message.contains("Frozone.power.level: u32 -> u64"));
+    assert!(message.contains("FROZONE_UPDATE=1"));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn lock_macro() {
+    let path = lockfile_path("macro");
+    let mut lockfile = Lockfile::open(&path);
+    lockfile
+        .update(true)
+        .check::<v1::Power>("Power")
+        .check::<Vec<u8>>("Vec<u8>")
+        .finish();
+
+    frozone::assert_locked!(&path, v1::Power, Vec<u8>);
+    let panic = std::panic::catch_unwind(|| frozone::assert_locked!(&path, v2::Power)).unwrap_err();
+    assert!(
+        panic
+            .downcast_ref::<String>()
+            .unwrap()
+            .contains("Power changed (freeze")
+    );
+
+    // entries are named after the schemas, not the spelling of the types
+    {
+        use v1::Power;
+        type Bytes = std::vec::Vec<u8>;
+        frozone::assert_locked!(&path, Power, Bytes, std::vec::Vec<u8>);
+    }
+    assert_eq!(
+        frozone::lock::entry_name::<[(u8, u16); 2]>(),
+        "[(u8, u16); 2]"
+    );
+    assert_eq!(
+        frozone::lock::entry_name::<Option<v1::Part>>(),
+        "Option<Part>"
+    );
+
+    // ... or explicit
+    Lockfile::open(&path)
+        .update(true)
+        .check::<v2::Power>("v2::Power")
+        .finish();
+    frozone::assert_locked!(&path, "Power" => v1::Power, "v2::Power" => v2::Power);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn lock_algorithms() {
+    let path = lockfile_path("algorithms");
+    Lockfile::open(&path)
+        .update(true)
+        .check_with::<v1::Frozone>("Frozone", Algorithm::V2(Profile::Serialization))
+        .check_with::<v1::Part>("Part", Algorithm::V2(Profile::Strict))
+        .finish();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.contains(&format!(
+        "[Frozone]\nalgorithm = v2 serialization\nfreeze = {}\n",
+        v1::Frozone::freeze_with(Profile::Serialization)
+    )));
+    assert!(text.contains(&format!(
+        "[Part]\nalgorithm = v2\nfreeze = {}\n",
+        v1::Part::freeze_v2()
+    )));
+
+    let mut lockfile = Lockfile::open(&path);
+    lockfile
+        .update(false)
+        .check_with::<v1::Frozone>("Frozone", Algorithm::V2(Profile::Serialization))
+        .check::<v1::Part>("Part");
+    assert_eq!(
+        lockfile.mismatches(),
+        ["Part is locked with the algorithm v2, not v1"]
+    );
+
+    frozone::assert_locked!(&path, with Algorithm::V2(Profile::Strict), v1::Part);
+    let panic = std::panic::catch_unwind(
+        || frozone::assert_locked!(&path, with Algorithm::V2(Profile::Strict), v2::Power),
+    )
+    .unwrap_err();
+    assert!(
+        panic
+            .downcast_ref::<String>()
+            .unwrap()
+            .contains("Power is not in the lockfile")
+    );
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn lock_conflicts() {
+    #[derive(Freezable)]
+    struct Wrapper<T: Freezable> {
+        inner: T,
+    }
+    let path = lockfile_path("conflicts");
+
+    // the instantiations of a generic type have to be given distinct names
+    assert_eq!(frozone::lock::entry_name::<Wrapper<u8>>(), "Wrapper");
+    let mut lockfile = Lockfile::open(&path);
+    lockfile
+        .update(true)
+        .check::<Wrapper<u8>>("Wrapper")
+        .check::<Wrapper<u16>>("Wrapper")
+        .check::<Wrapper<u8>>("Wrapper");
+    assert_eq!(
+        lockfile.conflicts(),
+        ["Wrapper is checked for different types (give them distinct entry names)"]
+    );
+    let panic = std::panic::catch_unwind(move || lockfile.finish()).unwrap_err();
+    assert!(
+        panic
+            .downcast_ref::<String>()
+            .unwrap()
+            .contains("Wrapper is checked for different types")
+    );
+    assert!(!path.exists());
+
+    Lockfile::open(&path)
+        .update(true)
+        .check::<Wrapper<u8>>("Wrapper<u8>")
+        .check::<Wrapper<u16>>("Wrapper<u16>")
+        .finish();
+    frozone::assert_locked!(&path, "Wrapper<u8>" => Wrapper<u8>, "Wrapper<u16>" => Wrapper<u16>);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn lock_concurrent_updates() {
+    let path = lockfile_path("concurrent");
+    // the lockfile is opened by every test before any of them updates it
+    let barrier = std::sync::Barrier::new(8);
+    std::thread::scope(|scope| {
+        for i in 0..8 {
+            let (path, barrier) = (&path, &barrier);
+            scope.spawn(move || {
+                let mut lockfile = Lockfile::open(path);
+                barrier.wait();
+                lockfile
+                    .update(true)
+                    .check::<v1::Part>(&format!("Part{}", i))
+                    .finish();
+            });
+        }
+    });
+
+    // no update dropped the entries of another
+    let mut lockfile = Lockfile::open(&path);
+    lockfile.update(false);
+    for i in 0..8 {
+        lockfile.check::<v1::Part>(&format!("Part{}", i));
+    }
+    assert!(lockfile.mismatches().is_empty());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn lock_malformed() {
+    let path = lockfile_path("malformed");
+    std::fs::write(
+        &path,
+        "# comment\n\n[Frozone]\nalgorithm = v1\nfreeze = abc\n",
+    )
+    .unwrap();
+    let panic = std::panic::catch_unwind(|| Lockfile::open(&path)).unwrap_err();
+    assert!(
+        panic
+            .downcast_ref::<String>()
+            .unwrap()
+            .ends_with(":5: expected a `freeze = N` line")
+    );
+
+    std::fs::write(&path, "[Frozone]\nfreeze = 0\n").unwrap();
+    let panic = std::panic::catch_unwind(|| Lockfile::open(&path)).unwrap_err();
+    assert!(
+        panic
+            .downcast_ref::<String>()
+            .unwrap()
+            .ends_with(":2: expected an `algorithm = ..` line")
+    );
+
+    std::fs::write(&path, "[Frozone]\nalgorithm = v3\nfreeze = 0\n").unwrap();
+    let panic = std::panic::catch_unwind(|| Lockfile::open(&path)).unwrap_err();
+    assert!(
+        panic
+            .downcast_ref::<String>()
+            .unwrap()
+            .ends_with(":2: unknown algorithm")
+    );
+
+    // schemas that can't be parsed are ignored, only the freeze is checked
+    std::fs::write(
+        &path,
+        format!(
+            "[Part]\nalgorithm = v1\nfreeze = {}\n(unknown)\n",
+            v1::Part::freeze()
+        ),
+    )
+    .unwrap();
+    let mut lockfile = Lockfile::open(&path);
+    lockfile.update(false).check::<v1::Part>("Part").finish();
+
+    std::fs::remove_file(&path).unwrap();
+}
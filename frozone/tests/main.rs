@@ -1,6 +1,7 @@
 #![allow(unused)]
 #![allow(unexpected_cfgs)]
-use frozone::Freezable;
+#![allow(clippy::identity_op)]
+use frozone::{Freezable, Schema};
 
 #[macro_use]
 extern crate static_assertions;
@@ -23,10 +24,10 @@ fn u64() {
     assert_eq!(std::primitive::u64::freeze(), 4711862451334276647);
 }
 
-// #[test]
-// fn const_eval() {
-//     static_assertions::const_assert_eq!(u64::freeze(), 4711862451334276647);
-// }
+#[test]
+fn const_eval() {
+    static_assertions::const_assert_eq!(u64::FREEZE, 4711862451334276647);
+}
 
 #[test]
 fn derive() {
@@ -57,6 +58,50 @@ fn derive() {
     }
 }
 
+// the derive doesn't import anything in the module of the type
+mod glob_imports {
+    mod other {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub struct Vec;
+        #[derive(Freezable)]
+        pub struct Box;
+    }
+    use frozone::Freezable;
+    use other::*;
+
+    #[derive(Freezable)]
+    pub struct Schema {
+        pub a: u8,
+    }
+
+    #[derive(Freezable)]
+    pub struct MyType {
+        pub schema: Schema,
+        pub b: [u16; 2],
+        pub c: (Vec, Box),
+    }
+}
+
+#[test]
+fn derive_hygiene() {
+    #[derive(Freezable)]
+    struct Schema {
+        a: u8,
+    }
+    #[derive(Freezable)]
+    struct Vec;
+    #[derive(Freezable)]
+    struct Box;
+    #[derive(Freezable)]
+    struct MyType {
+        schema: Schema,
+        b: [u16; 2],
+        c: (Vec, Box),
+    }
+    assert_eq!(glob_imports::MyType::freeze(), MyType::freeze());
+}
+
 #[test]
 fn derive_container() {
     #[derive(Freezable)]
@@ -374,6 +419,200 @@ fn external_generic() {
     assert_ne!(MyEnumType5::freeze(), MyEnumType6::freeze());
 }
 
+#[test]
+fn external_generic_type_shapes() {
+    struct External1<T> {
+        a: T,
+    }
+    struct External2<T> {
+        a: T,
+    }
+    trait Handler<T> {}
+
+    #[derive(Freezable)]
+    struct MyType1<T: Freezable + 'static> {
+        #[assume_frozen(freeze_generics)]
+        a: (External1<T>, [External1<u8>; 2]),
+        #[assume_frozen(freeze_generics)]
+        b: &'static [External1<T>],
+        #[assume_frozen(freeze_generics)]
+        c: *mut External1<T>,
+        #[assume_frozen(freeze_generics)]
+        d: &'static (dyn Handler<T> + Send),
+        #[assume_frozen(freeze_generics)]
+        e: fn(T) -> u8,
+    }
+    #[derive(Freezable)]
+    struct MyType2<T: Freezable + 'static> {
+        #[assume_frozen(freeze_generics)]
+        a: (External2<T>, [External2<u8>; 2]),
+        #[assume_frozen(freeze_generics)]
+        b: &'static [External2<T>],
+        #[assume_frozen(freeze_generics)]
+        c: *mut External2<T>,
+        #[assume_frozen(freeze_generics)]
+        d: &'static (dyn Handler<T> + Send),
+        #[assume_frozen(freeze_generics)]
+        e: fn(T) -> u8,
+    }
+    assert_eq!(MyType1::<u8>::freeze(), MyType2::<u8>::freeze());
+    assert_ne!(MyType1::<u8>::freeze(), MyType1::<u16>::freeze());
+    assert_eq!(MyType1::<u16>::FREEZE, MyType1::<u16>::freeze());
+
+    #[derive(Freezable)]
+    enum MyEnumType<T: Freezable + 'static> {
+        #[assume_frozen(freeze_generics)]
+        A(&'static mut (External1<T>, ())),
+    }
+    assert_ne!(MyEnumType::<u8>::freeze(), MyEnumType::<u16>::freeze());
+    assert_eq!(MyEnumType::<u8>::FREEZE, MyEnumType::<u8>::freeze());
+
+    // associated type bindings are frozen, by name
+    trait Stream {
+        type Item;
+        type Error;
+    }
+    #[derive(Freezable)]
+    struct Bytes {
+        #[assume_frozen(freeze_generics)]
+        a: &'static (dyn Stream<Item = u8, Error = ()> + Send),
+    }
+    #[derive(Freezable)]
+    struct Strings {
+        #[assume_frozen(freeze_generics)]
+        a: &'static (dyn Stream<Item = String, Error = ()> + Send),
+    }
+    #[derive(Freezable)]
+    struct Errors {
+        #[assume_frozen(freeze_generics)]
+        a: &'static (dyn Stream<Item = (), Error = u8> + Send),
+    }
+    assert_ne!(Bytes::freeze(), Strings::freeze());
+    assert_ne!(Bytes::freeze(), Errors::freeze());
+    assert_ne!(Bytes::freeze_v2(), Strings::freeze_v2());
+    assert_ne!(Bytes::freeze_v2(), Errors::freeze_v2());
+    assert_eq!(Bytes::FREEZE, Bytes::freeze());
+    let schema = Bytes::schema();
+    let text = schema.to_string();
+    assert!(
+        text.contains("(binding \"Item\" (opaque \"u8\"))"),
+        "{}",
+        text
+    );
+    assert_eq!(text.parse::<Schema>(), Ok(schema));
+}
+
+#[test]
+fn fn_pointers() {
+    assert_ne!(<fn(u8) -> u16>::freeze(), <fn(u16) -> u8>::freeze());
+    assert_ne!(<fn(u8, u16)>::freeze(), <fn(u8) -> u16>::freeze());
+    assert_eq!(<fn(u8)>::freeze(), <fn(u8) -> ()>::freeze());
+    // ABI and safety are frozen too
+    assert_ne!(<fn(u8)>::freeze(), <unsafe fn(u8)>::freeze());
+    assert_ne!(<fn(u8)>::freeze(), <extern "C" fn(u8)>::freeze());
+    assert_ne!(
+        <unsafe fn(u8)>::freeze(),
+        <unsafe extern "C" fn(u8)>::freeze()
+    );
+    assert_eq!(
+        <extern "C" fn(*const u8, usize) -> i32>::schema(),
+        Schema::Container {
+            name: "extern \"C\" fn".into(),
+            args: vec![<*const u8>::schema(), usize::schema(), i32::schema()],
+        }
+    );
+
+    #[derive(Freezable)]
+    struct Callbacks {
+        on_event: fn(&'static str, u32) -> bool,
+        free: unsafe extern "C" fn(*mut u8),
+        // higher-ranked fn pointers aren't `Freezable`, but their `'static` version is
+        #[frozone(as = fn(&'static str) -> bool)]
+        on_line: fn(&str) -> bool,
+    }
+    assert_eq!(Callbacks::FREEZE, Callbacks::freeze());
+    let Schema::Struct { fields, .. } = Callbacks::schema() else {
+        panic!("not a struct");
+    };
+    assert_eq!(fields[2].schema, <fn(&'static str) -> bool>::schema());
+}
+
+#[test]
+fn dyn_traits() {
+    assert_ne!(<dyn Fn(u8)>::freeze(), <dyn FnMut(u8)>::freeze());
+    assert_ne!(<dyn Fn(u8)>::freeze(), <dyn Fn(u8) + Send>::freeze());
+    assert_ne!(<dyn Fn(u8)>::freeze(), <fn(u8)>::freeze());
+    assert_eq!(
+        <dyn FnOnce(u8) -> u16 + Sync + Send>::freeze(),
+        <dyn FnOnce(u8) -> u16 + Send + Sync>::freeze()
+    );
+    assert_eq!(
+        Box::<dyn Fn(u8) -> u16 + Send>::schema(),
+        Schema::Container {
+            name: "Box".into(),
+            args: vec![Schema::Container {
+                name: "dyn Fn + Send".into(),
+                args: vec![u8::schema(), u16::schema()],
+            }],
+        }
+    );
+
+    trait Stream {
+        type Item;
+    }
+    frozone::freezable_dyn!(Stream => "my_crate::Stream", type Item);
+    trait Handler<Event, Context> {
+        type Output;
+        type Error;
+    }
+    frozone::freezable_dyn!(Handler<Event, Context> => "Handler", type Output, type Error);
+
+    assert_ne!(
+        <dyn Stream<Item = u8>>::freeze(),
+        <dyn Stream<Item = u16>>::freeze()
+    );
+    assert_ne!(
+        <dyn Stream<Item = u8>>::freeze(),
+        <dyn Stream<Item = u8> + Send>::freeze()
+    );
+    assert_eq!(
+        <dyn Stream<Item = u8> + Send + Sync>::schema(),
+        Schema::Container {
+            name: "dyn my_crate::Stream<Item = _> + Send + Sync".into(),
+            args: vec![u8::schema()],
+        }
+    );
+    assert_eq!(
+        <dyn Handler<u8, u16, Output = u32, Error = String>>::schema(),
+        Schema::Container {
+            name: "dyn Handler<_, _, Output = _, Error = _>".into(),
+            args: vec![u8::schema(), u16::schema(), u32::schema(), String::schema()],
+        }
+    );
+
+    #[derive(Freezable)]
+    struct Task {
+        stream: Option<Box<dyn Stream<Item = u8> + Send>>,
+        on_done: Box<dyn FnOnce(u32) -> bool + Send>,
+    }
+    assert_eq!(Task::FREEZE, Task::freeze());
+
+    // whatever the lifetime of the trait objects
+    #[derive(Freezable)]
+    struct Borrowed<'a> {
+        on_done: Box<dyn Fn(u32) -> bool + 'a>,
+        stream: Option<Box<dyn Stream<Item = &'a u8> + Send + 'a>>,
+        // higher-ranked `Fn*` aren't `Freezable`, but their `'static` version is
+        #[frozone(as = Box<dyn Fn(&'static str)>)]
+        on_line: Box<dyn Fn(&str)>,
+    }
+    let Schema::Struct { fields, .. } = Borrowed::schema() else {
+        panic!("not a struct");
+    };
+    assert_eq!(fields[0].schema, Box::<dyn Fn(u32) -> bool>::schema());
+    assert_eq!(fields[2].schema, Box::<dyn Fn(&'static str)>::schema());
+}
+
 #[test]
 fn struct_names_non_importance() {
     // names of the structs shouldn't matter to frozone
@@ -602,6 +841,52 @@ fn recursive_types_advanced() {
     assert_ne!(Cycle2_1::freeze(), Cycle3_1::freeze());
 }
 
+#[test]
+fn recursive_types_generic() {
+    #[derive(Freezable)]
+    struct Wrapper<T: Freezable> {
+        a: T,
+    }
+    // not a cycle: the inner `Wrapper<u8>` is another type
+    let schema = Wrapper::<Wrapper<u8>>::schema();
+    let frozone::Schema::Struct { fields, .. } = &schema else {
+        unreachable!()
+    };
+    assert_eq!(fields[0].schema, Wrapper::<u8>::schema());
+    assert_ne!(Wrapper::<Wrapper<u8>>::freeze(), Wrapper::<u8>::freeze());
+
+    // ... but `List<u8>` is
+    #[derive(Freezable)]
+    struct List<T: Freezable> {
+        item: T,
+        next: Option<Box<List<T>>>,
+    }
+    let frozone::Schema::Struct { fields, .. } = List::<u8>::schema() else {
+        unreachable!()
+    };
+    assert!(matches!(
+        fields[1].schema,
+        frozone::Schema::Container { ref args, .. } if matches!(
+            args[0],
+            frozone::Schema::Container { ref args, .. }
+                if matches!(args[0], frozone::Schema::Recursion { depth: 1, .. })
+        )
+    ));
+
+    // generic parameters don't have to be satisfied by `()`
+    trait NotForUnit {}
+    impl NotForUnit for u8 {}
+    #[derive(Freezable)]
+    struct Bounded<'a, T: Freezable + NotForUnit, const N: usize> {
+        a: &'a [T; N],
+    }
+    #[derive(Freezable)]
+    struct Concrete<'a> {
+        a: &'a [u8; 3],
+    }
+    assert_eq!(Bounded::<u8, 3>::freeze(), Concrete::freeze());
+}
+
 #[test]
 #[cfg(feature = "std")]
 fn complex() {
@@ -631,7 +916,6 @@ fn complex() {
         Default,
         Centered,
         Specific(Point),
-        #[assume_frozen] // TODO: limitation to fix
         SpecificWith(fn(Size, Size) -> Point),
     }
     #[derive(Freezable)]
@@ -708,8 +992,6 @@ fn complex() {
     }
     #[derive(Freezable)]
     pub struct Custom {
-        #[assume_frozen] // real limitation, Cow's generic type must impl clone
-        // for it to be freezable
         name: std::borrow::Cow<'static, str>,
         palette: Palette,
         extended: Extended,
@@ -802,20 +1084,17 @@ fn complex() {
     pub trait Stream {
         type Item;
     }
+    frozone::freezable_dyn!(Stream => "futures_core::Stream", type Item);
     pub type BoxStream<T> = core::pin::Pin<Box<dyn Stream<Item = T> + Send>>;
     #[derive(Freezable)]
     pub struct Task<T: Freezable> {
-        #[assume_frozen] // TODO: limitation to fix
         stream: Option<BoxStream<Action<T>>>,
         units: usize,
     }
 
     #[derive(Freezable)]
     pub struct Preset<State: Freezable, Message: Freezable> {
-        #[assume_frozen] // real limitation, Cow's generic type must impl clone
-        // for it to be freezable
         name: std::borrow::Cow<'static, str>,
-        #[assume_frozen] // TODO: limitation to fix
         boot: Box<dyn Fn() -> (State, Task<Message>)>,
     }
 
@@ -836,19 +1115,21 @@ fn complex() {
         A,
     }
 
-    impl Executor for () {}
-    impl Renderer for () {}
+    pub struct MyExecutor;
+    impl Executor for MyExecutor {}
+    pub struct MyRenderer;
+    impl Renderer for MyRenderer {}
 
     type Main = ();
     impl Program for Main {
         type State = MyState;
         type Message = MyMessage;
         type Theme = Theme;
-        type Executor = ();
-        type Renderer = ();
+        type Executor = MyExecutor;
+        type Renderer = MyRenderer;
     }
 
-    assert_eq!(Application::<Main>::freeze(), 1146165835468690553)
+    assert_eq!(Application::<Main>::freeze(), 3712633877950771613)
 }
 
 #[test]
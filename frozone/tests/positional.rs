@@ -0,0 +1,212 @@
+#![allow(unused)]
+use frozone::{Freezable, Schema};
+
+#[test]
+fn positional_struct() {
+    #[derive(Freezable)]
+    #[frozone(positional)]
+    struct AB {
+        a: u8,
+        b: u16,
+    }
+    #[derive(Freezable)]
+    #[frozone(positional)]
+    struct BA {
+        b: u16,
+        a: u8,
+    }
+    #[derive(Freezable)]
+    struct Unordered {
+        b: u16,
+        a: u8,
+    }
+    assert!(matches!(
+        AB::schema(),
+        Schema::Struct {
+            positional: true,
+            ..
+        }
+    ));
+    assert_ne!(AB::freeze_v2(), BA::freeze_v2());
+    assert_ne!(AB::freeze_v2(), Unordered::freeze_v2());
+    // v1 ignores the order
+    assert_eq!(AB::freeze_v1(), BA::freeze_v1());
+    assert_eq!(AB::freeze_v1(), Unordered::freeze_v1());
+
+    #[derive(Freezable)]
+    #[frozone(positional)]
+    struct Tuple(u8, u16);
+    #[derive(Freezable)]
+    #[frozone(positional)]
+    struct Swapped(u16, u8);
+    assert_ne!(Tuple::freeze_v2(), Swapped::freeze_v2());
+    assert_eq!(Tuple::freeze_v1(), Swapped::freeze_v1());
+
+    // skipped fields aren't serialized, so don't take a position
+    struct NotFreezable;
+    #[derive(Freezable)]
+    #[frozone(positional)]
+    struct Skipped {
+        #[serde(skip)]
+        cache: NotFreezable,
+        a: u8,
+        b: u16,
+    }
+    assert_eq!(Skipped::freeze_v2(), AB::freeze_v2());
+}
+
+#[test]
+fn positional_tuple_fields() {
+    mod a {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub struct P(pub u32, pub String);
+        #[derive(Freezable)]
+        pub enum E {
+            V(u32, String),
+        }
+    }
+    mod b {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub struct P(pub String, pub u32);
+        #[derive(Freezable)]
+        pub enum E {
+            V(String, u32),
+        }
+    }
+    mod positional {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        #[frozone(positional)]
+        pub struct P(pub u32, pub String);
+    }
+    // unnamed fields are positional without `#[frozone(positional)]`
+    assert_ne!(a::P::freeze_v2(), b::P::freeze_v2());
+    assert_eq!(a::P::freeze_v2(), positional::P::freeze_v2());
+    assert_ne!(a::E::freeze_v2(), b::E::freeze_v2());
+    // v1 only freezes the order of the fields of variants
+    assert_eq!(a::P::freeze_v1(), b::P::freeze_v1());
+    assert_ne!(a::E::freeze_v1(), b::E::freeze_v1());
+
+    let changes: Vec<_> = a::P::schema()
+        .diff(&b::P::schema())
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            "P.0: u32 -> alloc::string::String",
+            "P.1: alloc::string::String -> u32"
+        ]
+    );
+}
+
+#[test]
+fn positional_enum() {
+    #[derive(Freezable)]
+    #[frozone(positional)]
+    enum AB {
+        A,
+        B(u8),
+    }
+    #[derive(Freezable)]
+    #[frozone(positional)]
+    enum BA {
+        B(u8),
+        A,
+    }
+    assert_ne!(AB::freeze_v2(), BA::freeze_v2());
+    assert_eq!(AB::freeze_v1(), BA::freeze_v1());
+
+    // the position of a variant is its index, skipped variants included
+    #[derive(Freezable)]
+    #[frozone(positional)]
+    enum Skipped {
+        #[serde(skip)]
+        Internal,
+        A,
+        B(u8),
+    }
+    assert_ne!(Skipped::freeze_v2(), AB::freeze_v2());
+}
+
+#[test]
+fn positional_per_call() {
+    #[derive(Freezable)]
+    struct Inner {
+        x: u8,
+        y: u8,
+    }
+    #[derive(Freezable)]
+    struct Swapped {
+        y: u8,
+        x: u8,
+    }
+    #[derive(Freezable)]
+    struct Outer {
+        inner: Vec<Inner>,
+    }
+    #[derive(Freezable)]
+    #[frozone(positional)]
+    struct PositionalInner {
+        x: u8,
+        y: u8,
+    }
+    assert_eq!(Inner::freeze_v2(), Swapped::freeze_v2());
+    assert_ne!(Inner::freeze_positional(), Swapped::freeze_positional());
+    assert_eq!(Inner::freeze_positional(), PositionalInner::freeze_v2());
+
+    // nested types are positional too
+    let Schema::Struct { fields, .. } = Outer::schema().positional() else {
+        panic!("not a struct");
+    };
+    assert_eq!(
+        fields[0].schema,
+        Schema::Container {
+            name: "Vec".into(),
+            args: vec![Inner::schema().positional()],
+        }
+    );
+}
+
+#[test]
+fn positional_schema_text_and_diff() {
+    #[derive(Freezable)]
+    #[frozone(positional)]
+    enum MyEnum {
+        A,
+        B,
+        C,
+    }
+    let schema = MyEnum::schema();
+    assert!(
+        schema
+            .to_string()
+            .starts_with(r#"(enum "MyEnum" positional"#)
+    );
+    assert_eq!(schema.to_string().parse::<Schema>().unwrap(), schema);
+
+    let reordered: Schema =
+        r#"(enum "MyEnum" positional (variant "C" unit) (variant "A" unit) (variant "B" unit))"#
+            .parse()
+            .unwrap();
+    let changes: Vec<_> = schema
+        .diff(&reordered)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(changes, ["MyEnum: order (A, B, C) -> order (C, A, B)"]);
+
+    let unordered: Schema =
+        r#"(enum "MyEnum" (variant "A" unit) (variant "B" unit) (variant "C" unit))"#
+            .parse()
+            .unwrap();
+    let changes: Vec<_> = unordered
+        .diff(&schema)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(changes, ["MyEnum: unordered -> #[frozone(positional)]"]);
+}
@@ -0,0 +1,118 @@
+#![allow(unused)]
+use frozone::schema::Profile;
+use frozone::{Freezable, Schema};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, VecDeque};
+use std::num::{NonZeroU32, Wrapping};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32};
+
+fn serialization<T: Freezable + ?Sized>() -> u64 {
+    T::freeze_with(Profile::Serialization)
+}
+
+#[test]
+fn serialization_profile_sequences() {
+    let vec = serialization::<Vec<u8>>();
+    assert_eq!(serialization::<VecDeque<u8>>(), vec);
+    assert_eq!(serialization::<[u8]>(), vec);
+    assert_eq!(serialization::<&[u8]>(), vec);
+    assert_eq!(serialization::<BTreeSet<u8>>(), vec);
+    assert_eq!(serialization::<BinaryHeap<u8>>(), vec);
+    assert_eq!(serialization::<Box<[u8]>>(), vec);
+    assert_eq!(serialization::<Rc<[u8]>>(), vec);
+
+    // still told apart from other shapes
+    assert_ne!(serialization::<Vec<u16>>(), vec);
+    assert_ne!(serialization::<[u8; 4]>(), vec);
+    assert_ne!(serialization::<Option<u8>>(), serialization::<u8>());
+    assert_ne!(
+        serialization::<BTreeMap<u8, u8>>(),
+        serialization::<Vec<(u8, u8)>>()
+    );
+}
+
+#[test]
+fn serialization_profile_transparent() {
+    let u32 = serialization::<u32>();
+    assert_eq!(serialization::<Box<u32>>(), u32);
+    assert_eq!(serialization::<Rc<u32>>(), u32);
+    assert_eq!(serialization::<Arc<u32>>(), u32);
+    assert_eq!(serialization::<&u32>(), u32);
+    assert_eq!(serialization::<Cell<u32>>(), u32);
+    assert_eq!(serialization::<RefCell<u32>>(), u32);
+    assert_eq!(serialization::<Wrapping<u32>>(), u32);
+    assert_eq!(serialization::<AtomicU32>(), u32);
+    assert_eq!(serialization::<NonZeroU32>(), u32);
+    assert_eq!(serialization::<Arc<Box<u32>>>(), u32);
+    assert_eq!(serialization::<AtomicBool>(), serialization::<bool>());
+
+    let string = serialization::<String>();
+    assert_eq!(serialization::<&str>(), string);
+    assert_eq!(serialization::<Box<str>>(), string);
+    assert_eq!(serialization::<Cow<'static, str>>(), string);
+    assert_ne!(serialization::<char>(), string);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn serialization_profile_std() {
+    use std::path::PathBuf;
+    use std::sync::{Mutex, RwLock};
+
+    assert_eq!(serialization::<Mutex<u32>>(), serialization::<u32>());
+    assert_eq!(serialization::<RwLock<u32>>(), serialization::<u32>());
+    assert_eq!(serialization::<PathBuf>(), serialization::<String>());
+}
+
+#[test]
+fn serialization_profile_nested() {
+    #[derive(Freezable)]
+    struct Config {
+        names: Vec<String>,
+        shared: Arc<Vec<u8>>,
+        counter: u32,
+    }
+    #[derive(Freezable)]
+    struct Swapped {
+        names: VecDeque<Box<str>>,
+        shared: Box<[u8]>,
+        counter: AtomicU32,
+    }
+    #[derive(Freezable)]
+    enum E {
+        A(Rc<Config>),
+    }
+    #[derive(Freezable)]
+    enum F {
+        A(Swapped),
+    }
+    assert_eq!(serialization::<Config>(), serialization::<Swapped>());
+    assert_eq!(serialization::<E>(), serialization::<F>());
+
+    let fields = |schema: Schema| match schema.with_profile(Profile::Serialization) {
+        Schema::Struct { fields, .. } => fields,
+        _ => panic!("not a struct"),
+    };
+    assert_eq!(fields(Swapped::schema()), fields(Config::schema()));
+}
+
+#[test]
+fn strict_profile() {
+    assert_eq!(Profile::default(), Profile::Strict);
+    assert_eq!(
+        Vec::<u8>::freeze_with(Profile::Strict),
+        Vec::<u8>::freeze_v2()
+    );
+    assert_ne!(
+        Vec::<u8>::freeze_with(Profile::Strict),
+        VecDeque::<u8>::freeze_with(Profile::Strict)
+    );
+    assert_ne!(Box::<u8>::freeze_v2(), u8::freeze_v2());
+    assert_eq!(
+        VecDeque::<Box<u8>>::schema().with_profile(Profile::Strict),
+        VecDeque::<Box<u8>>::schema()
+    );
+}
@@ -0,0 +1,471 @@
+#![allow(unused)]
+use frozone::Freezable;
+use frozone::Schema;
+use frozone::schema::{Field, Shape, Variant};
+
+#[test]
+fn schema_freeze() {
+    #[derive(Freezable)]
+    struct MyType {
+        field_a: u64,
+        field_b: Vec<MySubType>,
+        field_c: (u8, [u16; 4]),
+    }
+    #[derive(Freezable)]
+    #[repr(u8)]
+    enum MySubType {
+        D = 3,
+        A,
+        B(u32, String),
+        C { a: Option<Box<MyType>> },
+    }
+
+    assert_eq!(MyType::schema().freeze(), MyType::freeze());
+    assert_eq!(MySubType::schema().freeze(), MySubType::freeze());
+}
+
+#[test]
+fn schema_struct() {
+    #[derive(Freezable)]
+    struct MyType {
+        a: u64,
+        b: Result<u8, String>,
+    }
+    assert_eq!(
+        MyType::schema(),
+        Schema::Struct {
+            name: "MyType".into(),
+            shape: Shape::Named,
+            fields: vec![
+                Field {
+                    name: "a".into(),
+                    schema: Schema::Opaque("u64".into()),
+                    serde: Default::default(),
+                },
+                Field {
+                    name: "b".into(),
+                    schema: Schema::Container {
+                        name: "Result".into(),
+                        args: vec![u8::schema(), String::schema()],
+                    },
+                    serde: Default::default(),
+                },
+            ],
+            serde: Default::default(),
+            positional: false,
+            layout: None,
+        }
+    );
+
+    #[derive(Freezable)]
+    struct MyTuple(u8, #[assume_frozen] u16);
+    assert_eq!(
+        MyTuple::schema(),
+        Schema::Struct {
+            name: "MyTuple".into(),
+            shape: Shape::Tuple,
+            fields: vec![
+                Field {
+                    name: "0".into(),
+                    schema: u8::schema(),
+                    serde: Default::default(),
+                },
+                Field {
+                    name: "1".into(),
+                    schema: Schema::AssumeFrozen,
+                    serde: Default::default(),
+                },
+            ],
+            serde: Default::default(),
+            positional: false,
+            layout: None,
+        }
+    );
+}
+
+#[test]
+fn schema_enum() {
+    #[derive(Freezable)]
+    enum MyType {
+        A = 1,
+        #[assume_frozen]
+        B,
+    }
+    let Schema::Enum { name, variants, .. } = MyType::schema() else {
+        panic!("not an enum");
+    };
+    assert_eq!(name, "MyType");
+    assert_eq!(variants.len(), 2);
+    assert_eq!(variants[0].name, "A");
+    assert_eq!(variants[0].shape, Shape::Unit);
+    assert_eq!(variants[0].discriminant.map(|d| d.value), Some(1));
+    assert!(variants[1].assume_frozen);
+}
+
+#[test]
+fn schema_shape() {
+    mod unit {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub struct A;
+        #[derive(Freezable)]
+        pub enum E {
+            V,
+        }
+    }
+    mod tuple {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub struct A();
+        #[derive(Freezable)]
+        pub enum E {
+            V(),
+        }
+    }
+    mod named {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub struct A {}
+        #[derive(Freezable)]
+        pub enum E {
+            V {},
+        }
+    }
+    // v1 only freezes the fields
+    assert_eq!(unit::A::freeze_v1(), tuple::A::freeze_v1());
+    assert_eq!(unit::A::freeze_v1(), named::A::freeze_v1());
+    assert_eq!(unit::E::freeze_v1(), named::E::freeze_v1());
+
+    let structs = [
+        unit::A::freeze_v2(),
+        tuple::A::freeze_v2(),
+        named::A::freeze_v2(),
+    ];
+    let enums = [
+        unit::E::freeze_v2(),
+        tuple::E::freeze_v2(),
+        named::E::freeze_v2(),
+    ];
+    for freezes in [structs, enums] {
+        assert_ne!(freezes[0], freezes[1]);
+        assert_ne!(freezes[0], freezes[2]);
+        assert_ne!(freezes[1], freezes[2]);
+    }
+
+    let changes = |old: Schema, new: Schema| -> Vec<_> {
+        old.diff(&new).iter().map(|c| c.to_string()).collect()
+    };
+    assert_eq!(
+        changes(unit::A::schema(), named::A::schema()),
+        ["A: unit -> named fields"]
+    );
+    assert_eq!(
+        changes(tuple::E::schema(), unit::E::schema()),
+        ["E::V: tuple -> unit"]
+    );
+}
+
+#[test]
+fn schema_variant_field_names() {
+    mod v1 {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub enum Command {
+            Move { x: i32, y: i32 },
+            Say(String, u8),
+        }
+    }
+    mod v2 {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub enum Command {
+            Move { dx: i32, y: i32 },
+            Say(String, u8),
+        }
+    }
+    mod reordered {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub enum Command {
+            Move { y: i32, x: i32 },
+            Say(String, u8),
+        }
+    }
+    // v1 only freezes the types of the fields of a variant
+    assert_eq!(v1::Command::freeze_v1(), v2::Command::freeze_v1());
+    assert_ne!(v1::Command::freeze_v2(), v2::Command::freeze_v2());
+    // ... and v2 freezes named fields as the ones of structs
+    assert_eq!(v1::Command::freeze_v2(), reordered::Command::freeze_v2());
+    assert_ne!(
+        v1::Command::freeze_positional(),
+        reordered::Command::freeze_positional()
+    );
+
+    let changes: Vec<_> = v1::Command::schema()
+        .diff(&v2::Command::schema())
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    assert_eq!(changes, ["Command::Move.x renamed to dx?"]);
+    assert!(
+        v1::Command::schema()
+            .diff(&reordered::Command::schema())
+            .is_empty()
+    );
+}
+
+#[test]
+fn schema_recursion() {
+    #[derive(Freezable)]
+    struct MyType1 {
+        a: Option<Box<MyType1>>,
+    }
+    let Schema::Struct { fields, .. } = MyType1::schema() else {
+        panic!("not a struct");
+    };
+    let Schema::Container { args, .. } = &fields[0].schema else {
+        panic!("not a container");
+    };
+    let Schema::Container { args, .. } = &args[0] else {
+        panic!("not a container");
+    };
+    assert_eq!(
+        args[0],
+        Schema::Recursion {
+            name: "MyType1".into(),
+            depth: 1
+        }
+    );
+}
+
+#[test]
+fn schema_text_roundtrip() {
+    #[derive(Freezable)]
+    struct MyType {
+        a: u64,
+        b: Vec<(u8, [MySubType; 3])>,
+        #[assume_frozen(freeze_generics)]
+        c: std::vec::Vec<MySubType>,
+        d: Option<Box<MyType>>,
+        #[assume_frozen]
+        e: u8,
+    }
+    #[derive(Freezable)]
+    #[repr(u8)]
+    enum MySubType {
+        A = 4,
+        B(u8, String),
+        #[assume_frozen]
+        C {
+            a: u32,
+        },
+        D {
+            a: Result<u8, ()>,
+            b: &'static str,
+        },
+    }
+    let schema = MyType::schema();
+    let text = schema.to_string();
+    assert_eq!(text.parse::<Schema>(), Ok(schema));
+
+    assert_eq!(
+        Schema::Opaque("a \"quoted\\\" name".into())
+            .to_string()
+            .parse::<Schema>(),
+        Ok(Schema::Opaque("a \"quoted\\\" name".into()))
+    );
+    assert!("(struct \"A\" named".parse::<Schema>().is_err());
+    assert!("(opaque \"u8\") (opaque \"u8\")".parse::<Schema>().is_err());
+    assert!("(unknown)".parse::<Schema>().is_err());
+}
+
+#[test]
+fn schema_diff() {
+    mod v1 {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub struct Frozone {
+            pub power: Power,
+            pub equipment: Vec<Part>,
+            pub super_suit: Suit,
+        }
+        #[derive(Freezable)]
+        pub struct Power {
+            pub level: u32,
+        }
+        #[derive(Freezable)]
+        pub enum Part {
+            Sunglasses,
+            Skates,
+        }
+        #[derive(Freezable)]
+        pub struct Suit {
+            pub colour: String,
+            pub size: u8,
+        }
+    }
+    mod v2 {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub struct Frozone {
+            pub equipment: Vec<Part>, // reordered
+            pub power: Power,
+            pub super_suit: Suit,
+            pub age: u8,
+        }
+        #[derive(Freezable)]
+        pub struct Power {
+            pub level: u64,
+        }
+        #[derive(Freezable)]
+        pub enum Part {
+            Sunglasses,
+        }
+        #[derive(Freezable)]
+        pub struct Suit {
+            pub size: u8,
+            pub color: String,
+        }
+    }
+    let changes: Vec<_> = v1::Frozone::schema()
+        .diff(&v2::Frozone::schema())
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            "Frozone.power.level: u32 -> u64",
+            "Frozone.equipment::Skates removed",
+            "Frozone.super_suit.colour renamed to color?",
+            "Frozone.age added",
+        ]
+    );
+
+    assert!(
+        v1::Frozone::schema()
+            .diff(&v1::Frozone::schema())
+            .is_empty()
+    );
+
+    // variants are renamed if an added one has the same freeze
+    mod v3 {
+        use frozone::Freezable;
+        #[derive(Freezable)]
+        pub enum Part {
+            Glasses,
+            Boots(u8),
+        }
+    }
+    let changes: Vec<_> = v1::Part::schema()
+        .diff(&v3::Part::schema())
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            "Part::Sunglasses renamed to Glasses?",
+            "Part::Skates removed",
+            "Part::Boots added",
+        ]
+    );
+
+    // the freezes of deeply nested schemas are computed once
+    let nested = |depth: usize, leaf: &str| -> Schema {
+        let containers = r#"(container "Option" "#.repeat(depth);
+        let text = format!(
+            r#"(struct "Deep" named (field "a" {}(opaque "{}"){}))"#,
+            containers,
+            leaf,
+            ")".repeat(depth)
+        );
+        text.parse().unwrap()
+    };
+    let changes: Vec<_> = nested(100, "u32")
+        .diff(&nested(100, "u64"))
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    assert_eq!(changes, ["Deep.a: u32 -> u64"]);
+}
+
+#[test]
+fn schema_visit() {
+    #[derive(Freezable)]
+    struct MyType {
+        a: u64,
+        b: Vec<MyEnum>,
+    }
+    #[derive(Freezable)]
+    enum MyEnum {
+        A,
+        B(u8, Option<Box<MyType>>),
+    }
+
+    let mut text = String::new();
+    MyType::display_to(&mut text).unwrap();
+    let expected = "\
+(MyType)
+ - a : 0x4163e5ef5a47e627
+ - b : 0x1f1534296e8c0270
+\t(MyEnum)
+\t - A : 0x7bf55e51b22b9698
+\t - B : 0x37ced9e639a30e98
+\t\t(B.0): 0x6c49acb5e849d0e3
+\t\t(B.1): 0xc6119852815a339b
+";
+    assert_eq!(text, expected);
+
+    /// collects the fields and variants along with their freeze
+    #[derive(Default)]
+    struct Freezes(Vec<(String, u64)>);
+    impl frozone::schema::Visitor for Freezes {
+        fn visit_field(&mut self, _depth: usize, name: &str, freeze: u64) -> std::fmt::Result {
+            self.0.push((name.to_string(), freeze));
+            Ok(())
+        }
+        fn visit_variant(&mut self, _depth: usize, name: &str, freeze: u64) -> std::fmt::Result {
+            self.0.push((format!("::{}", name), freeze));
+            Ok(())
+        }
+    }
+    let mut freezes = Freezes::default();
+    MyEnum::schema().visit(&mut freezes).unwrap();
+    let names: Vec<_> = freezes.0.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, ["::A", "::B", "a", "b"]);
+    assert_eq!(freezes.0[2].1, u64::freeze());
+
+    // the freezes of the types nested in tuples and arrays are their own
+    #[derive(Freezable)]
+    struct Leaf {
+        a: u64,
+        b: Vec<u8>,
+    }
+    #[derive(Freezable)]
+    struct Nested {
+        t: (u8, [Leaf; 2]),
+    }
+    let mut freezes = Freezes::default();
+    Nested::schema().visit(&mut freezes).unwrap();
+    assert_eq!(
+        freezes.0,
+        [
+            ("t".to_string(), <(u8, [Leaf; 2])>::freeze()),
+            ("a".to_string(), u64::freeze()),
+            ("b".to_string(), <Vec<u8>>::freeze()),
+        ]
+    );
+
+    // the walk stops at the first error
+    struct Failing(usize);
+    impl frozone::schema::Visitor for Failing {
+        fn visit_type(&mut self, _depth: usize, _name: &str) -> std::fmt::Result {
+            self.0 += 1;
+            Err(std::fmt::Error)
+        }
+    }
+    let mut failing = Failing(0);
+    assert!(MyType::schema().visit(&mut failing).is_err());
+    assert_eq!(failing.0, 1);
+}
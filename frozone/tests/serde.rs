@@ -0,0 +1,522 @@
+#![allow(unused, non_snake_case, non_camel_case_types)]
+use frozone::schema::{Deserialized, Representation, Serde, Serialized, Variant};
+use frozone::{Freezable, Schema};
+
+/// `serde` of the fields of a struct
+fn field_serdes<T: Freezable>() -> Vec<Serde> {
+    match T::schema() {
+        Schema::Struct { fields, .. } => fields.into_iter().map(|f| f.serde).collect(),
+        _ => panic!("not a struct"),
+    }
+}
+
+fn variants<T: Freezable>() -> Vec<Variant> {
+    match T::schema() {
+        Schema::Enum { variants, .. } => variants,
+        _ => panic!("not an enum"),
+    }
+}
+
+fn serialized(name: &'static str) -> Serde {
+    Serde {
+        serialize_name: Some(name.into()),
+        deserialize_name: Some(name.into()),
+        aliases: Vec::new(),
+        ..Serde::default()
+    }
+}
+
+#[test]
+fn serde_rename() {
+    #[derive(Freezable)]
+    struct Renamed {
+        #[serde(rename = "old")]
+        new: u8,
+        other: u16,
+    }
+    #[derive(Freezable)]
+    struct Old {
+        old: u8,
+        other: u16,
+    }
+    assert_eq!(
+        field_serdes::<Renamed>(),
+        [serialized("old"), Serde::default()]
+    );
+
+    // wire-compatible: same v2 freeze, v1 only freezes the Rust names
+    assert_eq!(Renamed::freeze_v2(), Old::freeze_v2());
+    assert_ne!(Renamed::freeze_v1(), Old::freeze_v1());
+
+    #[derive(Freezable)]
+    struct Split {
+        #[serde(rename(serialize = "old"))]
+        new: u8,
+        other: u16,
+    }
+    assert_eq!(
+        field_serdes::<Split>()[0],
+        Serde {
+            serialize_name: Some("old".into()),
+            deserialize_name: None,
+            aliases: Vec::new(),
+            ..Serde::default()
+        }
+    );
+    assert_ne!(Split::freeze_v2(), Old::freeze_v2());
+
+    #[derive(Freezable)]
+    struct Raw {
+        r#type: u8,
+    }
+    #[derive(Freezable)]
+    struct Type {
+        #[serde(rename = "type")]
+        kind: u8,
+    }
+    assert_eq!(field_serdes::<Raw>(), [serialized("type")]);
+    assert_eq!(Raw::freeze_v2(), Type::freeze_v2());
+}
+
+#[test]
+fn serde_rename_all() {
+    #[derive(Freezable)]
+    #[serde(rename_all = "camelCase", deny_unknown_fields)]
+    struct Camel {
+        my_field: u8,
+        #[serde(rename = "explicit", default)]
+        other_field: u16,
+    }
+    #[derive(Freezable)]
+    struct Manual {
+        myField: u8,
+        #[serde(default)]
+        explicit: u16,
+    }
+    assert_eq!(Camel::freeze_v2(), Manual::freeze_v2());
+
+    #[derive(Freezable)]
+    #[serde(rename_all = "snake_case", rename_all_fields = "UPPERCASE")]
+    enum MyEnum {
+        UnitVariant,
+        #[serde(rename_all = "kebab-case")]
+        StructVariant {
+            some_field: u8,
+        },
+        Other {
+            some_field: u8,
+        },
+    }
+    let variants = variants::<MyEnum>();
+    assert_eq!(variants[0].serde, serialized("unit_variant"));
+    assert_eq!(variants[1].serde, serialized("struct_variant"));
+    assert_eq!(variants[1].fields[0].serde, serialized("some-field"));
+    assert_eq!(variants[2].fields[0].serde, serialized("SOME_FIELD"));
+
+    #[derive(Freezable)]
+    #[serde(rename_all(serialize = "PascalCase"))]
+    struct SerializeOnly {
+        my_field: u8,
+    }
+    assert_eq!(
+        field_serdes::<SerializeOnly>()[0],
+        Serde {
+            serialize_name: Some("MyField".into()),
+            deserialize_name: None,
+            aliases: Vec::new(),
+            ..Serde::default()
+        }
+    );
+}
+
+#[test]
+fn serde_rename_rules() {
+    macro_rules! rules {
+        ($($rule:literal => $field:literal $variant:literal),* $(,)?) => {$({
+            #[derive(Freezable)]
+            #[serde(rename_all = $rule)]
+            struct S {
+                some_field_name: u8,
+            }
+            #[derive(Freezable)]
+            #[serde(rename_all = $rule)]
+            enum E {
+                SomeVariantName,
+            }
+            let field = field_serdes::<S>().remove(0).serialize_name;
+            assert_eq!(field.as_deref(), Some($field).filter(|f| *f != "some_field_name"), $rule);
+            let variant = variants::<E>().remove(0).serde.serialize_name;
+            assert_eq!(variant.as_deref(), Some($variant).filter(|v| *v != "SomeVariantName"), $rule);
+        })*};
+    }
+    rules!(
+        "lowercase" => "some_field_name" "somevariantname",
+        "UPPERCASE" => "SOME_FIELD_NAME" "SOMEVARIANTNAME",
+        "PascalCase" => "SomeFieldName" "SomeVariantName",
+        "camelCase" => "someFieldName" "someVariantName",
+        "snake_case" => "some_field_name" "some_variant_name",
+        "SCREAMING_SNAKE_CASE" => "SOME_FIELD_NAME" "SOME_VARIANT_NAME",
+        "kebab-case" => "some-field-name" "some-variant-name",
+        "SCREAMING-KEBAB-CASE" => "SOME-FIELD-NAME" "SOME-VARIANT-NAME",
+    );
+}
+
+#[test]
+fn serde_alias() {
+    #[derive(Freezable)]
+    struct Aliased {
+        #[serde(alias = "b", alias = "a")]
+        field: u8,
+    }
+    #[derive(Freezable)]
+    struct Reordered {
+        #[serde(alias = "a")]
+        #[serde(alias = "b")]
+        field: u8,
+    }
+    #[derive(Freezable)]
+    struct NoAlias {
+        field: u8,
+    }
+    assert_eq!(field_serdes::<Aliased>()[0].aliases, ["a", "b"]);
+    assert_eq!(Aliased::freeze_v2(), Reordered::freeze_v2());
+    assert_ne!(Aliased::freeze_v2(), NoAlias::freeze_v2());
+    assert_eq!(Aliased::freeze_v1(), NoAlias::freeze_v1());
+
+    #[derive(Freezable)]
+    enum MyEnum {
+        #[serde(rename = "a", alias = "old_a")]
+        A,
+    }
+    assert_eq!(
+        variants::<MyEnum>()[0].serde,
+        Serde {
+            serialize_name: Some("a".into()),
+            deserialize_name: Some("a".into()),
+            aliases: vec!["old_a".into()],
+            ..Serde::default()
+        }
+    );
+}
+
+#[test]
+fn serde_schema_text() {
+    #[derive(Freezable)]
+    #[serde(rename_all = "camelCase")]
+    enum MyEnum {
+        #[serde(alias = "b")]
+        VariantA { my_field: u8 },
+    }
+    let schema = MyEnum::schema();
+    let text = schema.to_string();
+    assert!(
+        text.contains(r#"(serde (serialize "variantA") (deserialize "variantA") (alias "b"))"#)
+    );
+    assert_eq!(text.parse::<Schema>().unwrap(), schema);
+
+    #[derive(Freezable)]
+    enum Renamed {
+        #[serde(rename = "variantA")]
+        VariantA { my_field: u8 },
+    }
+    let changes: Vec<_> = schema
+        .diff(&Renamed::schema())
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            r#"MyEnum::VariantA: #[serde(rename = "variantA", alias = "b")] -> #[serde(rename = "variantA")]"#
+        ]
+    );
+}
+
+#[test]
+fn serde_skip() {
+    // not `Freezable`
+    struct Cache;
+
+    #[derive(Freezable)]
+    struct Skipped {
+        field: u8,
+        #[serde(skip)]
+        cache: Cache,
+    }
+    #[derive(Freezable)]
+    struct WithoutCache {
+        field: u8,
+    }
+    #[derive(Freezable)]
+    struct AssumeFrozen {
+        field: u8,
+        #[assume_frozen]
+        cache: Cache,
+    }
+    let cache = &field_serdes::<Skipped>()[1];
+    assert!(cache.is_skipped());
+    assert_eq!(
+        (cache.serialized, cache.deserialized),
+        (Serialized::Never, Deserialized::Never)
+    );
+
+    // v1 freezes the skipped field by its name, v2 leaves it out
+    assert_eq!(Skipped::freeze_v1(), AssumeFrozen::freeze_v1());
+    assert_eq!(Skipped::FREEZE, Skipped::freeze_v1());
+    assert_eq!(Skipped::freeze_v2(), WithoutCache::freeze_v2());
+
+    #[derive(Freezable)]
+    enum MyEnum {
+        A(u8, #[serde(skip)] Cache),
+    }
+    #[derive(Freezable)]
+    enum WithoutCacheEnum {
+        A(u8),
+    }
+    assert_eq!(MyEnum::freeze_v2(), WithoutCacheEnum::freeze_v2());
+    assert_eq!(MyEnum::FREEZE, MyEnum::freeze_v1());
+}
+
+#[test]
+fn serde_presence() {
+    #[derive(Freezable)]
+    struct Required {
+        field: Option<u8>,
+    }
+    #[derive(Freezable)]
+    struct Defaulted {
+        #[serde(default)]
+        field: Option<u8>,
+    }
+    #[derive(Freezable)]
+    #[serde(default)]
+    struct ContainerDefault {
+        field: Option<u8>,
+    }
+    #[derive(Freezable)]
+    struct DefaultPath {
+        #[serde(default = "Default::default")]
+        field: Option<u8>,
+    }
+    #[derive(Freezable)]
+    struct Conditionally {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        field: Option<u8>,
+    }
+    #[derive(Freezable)]
+    struct SerializeOnly {
+        #[serde(skip_deserializing)]
+        field: Option<u8>,
+    }
+    #[derive(Freezable)]
+    struct DeserializeOnly {
+        #[serde(skip_serializing, default)]
+        field: Option<u8>,
+    }
+    let presence = |serde: Serde| (serde.serialized, serde.deserialized);
+    let defaulted = (Serialized::Always, Deserialized::Defaulted);
+    assert_eq!(presence(field_serdes::<Defaulted>().remove(0)), defaulted);
+    assert_eq!(
+        presence(field_serdes::<ContainerDefault>().remove(0)),
+        defaulted
+    );
+    assert_eq!(presence(field_serdes::<DefaultPath>().remove(0)), defaulted);
+    assert_eq!(
+        presence(field_serdes::<Conditionally>().remove(0)),
+        (Serialized::Conditionally, Deserialized::Required)
+    );
+    assert_eq!(
+        presence(field_serdes::<SerializeOnly>().remove(0)),
+        (Serialized::Always, Deserialized::Never)
+    );
+    assert_eq!(
+        presence(field_serdes::<DeserializeOnly>().remove(0)),
+        (Serialized::Never, Deserialized::Defaulted)
+    );
+
+    // only v2 freezes the presence
+    let freezes = [
+        Required::freeze_v2(),
+        Defaulted::freeze_v2(),
+        Conditionally::freeze_v2(),
+        SerializeOnly::freeze_v2(),
+        DeserializeOnly::freeze_v2(),
+    ];
+    for (i, a) in freezes.iter().enumerate() {
+        for b in &freezes[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+    assert_eq!(Defaulted::freeze_v2(), ContainerDefault::freeze_v2());
+    assert_eq!(Defaulted::freeze_v2(), DefaultPath::freeze_v2());
+    assert_eq!(Required::freeze_v1(), Defaulted::freeze_v1());
+    assert_eq!(Required::freeze_v1(), Conditionally::freeze_v1());
+
+    let schema = DeserializeOnly::schema();
+    let text = schema.to_string();
+    assert!(text.contains(r#"(field "field" (serde (skip_serializing) (default))"#));
+    assert_eq!(text.parse::<Schema>().unwrap(), schema);
+
+    let changes: Vec<_> = Required::schema()
+        .diff(&Defaulted::schema())
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        changes,
+        ["Required.field: no #[serde] attributes -> #[serde(default)]"]
+    );
+}
+
+#[test]
+fn serde_enum_tagging() {
+    macro_rules! tagged {
+        ($($(#[$attr:meta])* $name:ident),*) => {$(
+            #[derive(Freezable)]
+            $(#[$attr])*
+            enum $name {
+                A { a: u8 },
+                B(u16),
+            }
+        )*};
+    }
+    tagged!(
+        External,
+        #[serde(tag = "type")]
+        Internal,
+        #[serde(tag = "type", content = "content")]
+        Adjacent,
+        #[serde(tag = "kind", content = "content")]
+        OtherTag,
+        #[serde(untagged)]
+        Untagged
+    );
+    let representation = |schema: Schema| match schema {
+        Schema::Enum { serde, .. } => serde,
+        _ => panic!("not an enum"),
+    };
+    assert_eq!(representation(External::schema()), Representation::Default);
+    assert_eq!(
+        representation(Internal::schema()),
+        Representation::Internal { tag: "type".into() }
+    );
+    assert_eq!(
+        representation(Adjacent::schema()),
+        Representation::Adjacent {
+            tag: "type".into(),
+            content: "content".into()
+        }
+    );
+    assert_eq!(representation(Untagged::schema()), Representation::Untagged);
+
+    let freezes = [
+        External::freeze_v2(),
+        Internal::freeze_v2(),
+        Adjacent::freeze_v2(),
+        OtherTag::freeze_v2(),
+        Untagged::freeze_v2(),
+    ];
+    for (i, a) in freezes.iter().enumerate() {
+        for b in &freezes[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+    assert_eq!(External::freeze_v1(), Untagged::freeze_v1());
+
+    for schema in [Internal::schema(), Adjacent::schema(), Untagged::schema()] {
+        assert_eq!(schema.to_string().parse::<Schema>().unwrap(), schema);
+    }
+    let changes: Vec<_> = Internal::schema()
+        .diff(&Adjacent::schema())
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        changes,
+        [r#"Internal: #[serde(tag = "type")] -> #[serde(tag = "type", content = "content")]"#]
+    );
+}
+
+#[test]
+fn serde_transparent() {
+    #[derive(Freezable)]
+    #[serde(transparent)]
+    struct Transparent {
+        inner: u32,
+    }
+    #[derive(Freezable)]
+    #[serde(transparent)]
+    struct Renamed {
+        other: u32,
+    }
+    #[derive(Freezable)]
+    #[serde(transparent)]
+    struct Tuple(u32);
+    #[derive(Freezable)]
+    struct Plain {
+        inner: u32,
+    }
+    let schema = Transparent::schema();
+    assert!(matches!(
+        schema,
+        Schema::Struct {
+            serde: Representation::Transparent,
+            ..
+        }
+    ));
+    assert!(
+        schema
+            .to_string()
+            .starts_with(r#"(struct "Transparent" named (serde (transparent)) (field"#)
+    );
+    assert_eq!(schema.to_string().parse::<Schema>().unwrap(), schema);
+
+    // the name of the field isn't serialized
+    assert_eq!(Transparent::freeze_v2(), Renamed::freeze_v2());
+    assert_eq!(Transparent::freeze_v2(), Tuple::freeze_v2());
+    assert_ne!(Transparent::freeze_v2(), Plain::freeze_v2());
+    assert_eq!(Transparent::freeze_v1(), Plain::freeze_v1());
+}
+
+#[test]
+fn serde_flatten() {
+    #[derive(Freezable)]
+    struct Inner {
+        a: u8,
+        b: u16,
+    }
+    #[derive(Freezable)]
+    struct Flattened {
+        id: u32,
+        #[serde(flatten)]
+        inner: Inner,
+    }
+    #[derive(Freezable)]
+    struct RenamedFlattened {
+        id: u32,
+        #[serde(flatten)]
+        other: Inner,
+    }
+    #[derive(Freezable)]
+    struct Nested {
+        id: u32,
+        inner: Inner,
+    }
+    assert!(field_serdes::<Flattened>()[1].flatten);
+    assert_ne!(Flattened::freeze_v2(), Nested::freeze_v2());
+    assert_eq!(Flattened::freeze_v2(), RenamedFlattened::freeze_v2());
+    assert_eq!(Flattened::freeze_v1(), Nested::freeze_v1());
+
+    let schema = Flattened::schema();
+    assert!(schema.to_string().contains("(serde (flatten))"));
+    assert_eq!(schema.to_string().parse::<Schema>().unwrap(), schema);
+    let changes: Vec<_> = Nested::schema()
+        .diff(&schema)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        changes,
+        ["Nested.inner: no #[serde] attributes -> #[serde(flatten)]"]
+    );
+}
@@ -0,0 +1,225 @@
+#![allow(unused)]
+use frozone::Freezable;
+use frozone::hash::siphash;
+
+/// `str(s)` of the v1 specification
+fn str_bytes(s: &str) -> Vec<u8> {
+    let mut bytes = s.as_bytes().to_vec();
+    bytes.push(0xff);
+    bytes
+}
+
+/// `siphash(str(name) + u64(freeze))`
+fn named(name: &str, freeze: u64) -> u64 {
+    let mut bytes = str_bytes(name);
+    bytes.extend(freeze.to_le_bytes());
+    siphash(&bytes)
+}
+
+#[test]
+fn v1_spec() {
+    #[derive(Freezable)]
+    struct MyType {
+        a: u64,
+        b: Option<u8>,
+    }
+    #[derive(Freezable)]
+    enum MyEnum {
+        A,
+        B(u8),
+    }
+
+    // recomputed from the specification of `frozone::hash::v1`
+    let u64_freeze = siphash(&str_bytes("u64"));
+    let u8_freeze = siphash(&str_bytes("u8"));
+    let mut option = str_bytes("Option");
+    option.extend(u8_freeze.to_le_bytes());
+    let option_freeze = siphash(&option);
+    let my_type = named("a", u64_freeze).wrapping_add(named("b", option_freeze));
+    assert_eq!(MyType::freeze_v1(), my_type);
+
+    let a = siphash(&0i32.to_le_bytes());
+    let mut b = 0i32.to_le_bytes().to_vec();
+    b.extend(u8_freeze.to_le_bytes());
+    let b = siphash(&b);
+    let my_enum = named("A", a).wrapping_add(named("B", b));
+    assert_eq!(MyEnum::freeze_v1(), my_enum);
+}
+
+#[test]
+fn v1_is_the_default() {
+    #[derive(Freezable)]
+    struct MyType {
+        a: Vec<(u8, String)>,
+    }
+    assert_eq!(MyType::freeze(), MyType::freeze_v1());
+    assert_eq!(MyType::FREEZE, MyType::FREEZE_V1);
+    assert_eq!(MyType::FREEZE_V1, MyType::freeze_v1());
+    assert_eq!(MyType::schema().freeze(), MyType::schema().freeze_v1());
+
+    // freezes pinned with frozone 0.4.2
+    assert_eq!(u64::freeze_v1(), 4711862451334276647);
+    assert_eq!(String::FREEZE_V1, String::freeze());
+}
+
+/// integers (including `usize` lengths and `isize` discriminants) are encoded
+/// the same way on every target, as little-endian 64-bit (or the discriminant's
+/// own size) integers
+#[test]
+fn v1_target_independent() {
+    let u8_freeze = siphash(&str_bytes("u8"));
+
+    // `[u8; 3]`: the length is a u64, even on 32-bit targets
+    let mut array = str_bytes("[;N]");
+    array.extend(3u64.to_le_bytes());
+    array.extend(u8_freeze.to_le_bytes());
+    assert_eq!(<[u8; 3]>::freeze_v1(), siphash(&array));
+
+    // 3-tuples hash a u64 `1`
+    let mut tuple = str_bytes("(,)");
+    tuple.extend(1u64.to_le_bytes());
+    for _ in 0..3 {
+        tuple.extend(u8_freeze.to_le_bytes());
+    }
+    assert_eq!(<(u8, u8, u8)>::freeze_v1(), siphash(&tuple));
+
+    // `isize` discriminants are 8 bytes, even on 32-bit targets
+    #[derive(Freezable)]
+    #[repr(isize)]
+    enum Signed {
+        A = -2isize,
+    }
+    let discriminant = (-2i64).to_le_bytes();
+    let mut a = discriminant.to_vec();
+    a.extend(siphash(&discriminant).to_le_bytes());
+    assert_eq!(Signed::freeze_v1(), named("A", siphash(&a)));
+
+    // ... other discriminants are the size of their expression's type
+    #[derive(Freezable)]
+    #[repr(u16)]
+    enum Small {
+        A = 0x0102u16,
+        B = 3,
+    }
+    let discriminant = 0x0102u16.to_le_bytes();
+    let mut a = discriminant.to_vec();
+    a.extend(siphash(&discriminant).to_le_bytes());
+    let small_a = named("A", siphash(&a));
+    // (unsuffixed literals being `i32`)
+    let discriminant = 3i32.to_le_bytes();
+    let mut a = discriminant.to_vec();
+    a.extend(siphash(&discriminant).to_le_bytes());
+    assert_eq!(
+        Small::freeze_v1(),
+        small_a.wrapping_add(named("B", siphash(&a)))
+    );
+
+    let hasher = frozone::hash::Hasher::new();
+    assert_eq!(
+        hasher.write_u64(0x0102030405060708).finish(),
+        siphash(&[8, 7, 6, 5, 4, 3, 2, 1])
+    );
+}
+
+#[test]
+fn v2_recursion_independent_of_entry_point() {
+    use frozone::hash::v2;
+
+    #[derive(Freezable)]
+    struct Cycle2_1 {
+        a: Box<Option<Cycle2_2>>,
+    }
+    #[derive(Freezable)]
+    struct Cycle2_2 {
+        a: Box<Option<Cycle2_1>>,
+    }
+    #[derive(Freezable)]
+    struct Cycle3_1 {
+        a: Box<Option<Cycle3_2>>,
+    }
+    #[derive(Freezable)]
+    struct Cycle3_2 {
+        a: Box<Option<Cycle3_3>>,
+    }
+    #[derive(Freezable)]
+    struct Cycle3_3 {
+        a: Box<Option<Cycle3_1>>,
+    }
+    #[derive(Freezable)]
+    struct Outer {
+        cycle: Cycle2_1,
+        nested: Option<Inner>,
+    }
+    #[derive(Freezable)]
+    struct Inner {
+        cycle: Cycle2_1,
+    }
+
+    // the cycle contributes its own freeze, whatever its depth in `Outer`
+    let inner = v2::named("cycle", Cycle2_1::freeze_v2());
+    assert_eq!(Inner::freeze_v2(), inner);
+    let option = v2::container("Option", &[inner]);
+    assert_eq!(
+        Outer::freeze_v2(),
+        v2::named("cycle", Cycle2_1::freeze_v2()).wrapping_add(v2::named("nested", option))
+    );
+    // (which it doesn't with v1)
+    assert_ne!(Inner::freeze_v1(), named("cycle", Cycle2_1::freeze_v1()));
+
+    // cycles of different lengths still differ
+    assert_eq!(Cycle2_1::freeze_v2(), Cycle2_2::freeze_v2());
+    assert_eq!(Cycle3_1::freeze_v2(), Cycle3_3::freeze_v2());
+    assert_ne!(Cycle2_1::freeze_v2(), Cycle3_1::freeze_v2());
+
+    // ... as cycles aren't minimized: `A -> A` unrolled as `A -> B -> A`
+    // doesn't have the same freeze, though they (de)serialize the same
+    #[derive(Freezable)]
+    struct SelfA {
+        a: Option<Box<SelfA>>,
+    }
+    #[derive(Freezable)]
+    struct UnrolledA {
+        a: Option<Box<UnrolledB>>,
+    }
+    #[derive(Freezable)]
+    struct UnrolledB {
+        a: Option<Box<UnrolledA>>,
+    }
+    assert_ne!(SelfA::freeze_v2(), UnrolledA::freeze_v2());
+    // (the back-edge being 1 struct away from `SelfA`, and 2 from `UnrolledA`)
+    let field = |freeze| {
+        v2::named(
+            "a",
+            v2::container("Option", &[v2::container("Box", &[freeze])]),
+        )
+    };
+    assert_eq!(SelfA::freeze_v2(), field(v2::recursion(1)));
+    assert_eq!(UnrolledA::freeze_v2(), field(field(v2::recursion(2))));
+
+    // non-recursive types have the same freeze as with v1 (so far)
+    assert_eq!(
+        <Vec<(u8, String)>>::freeze_v2(),
+        <Vec<(u8, String)>>::freeze_v1()
+    );
+}
+
+/// the second exception to v1 giving the freezes of frozone 0.4.2: nested
+/// instantiations of a generic type are frozen as distinct types
+#[test]
+fn v1_nested_generic_instantiations() {
+    #[derive(Freezable)]
+    struct Wrapper<T: Freezable> {
+        a: T,
+    }
+
+    let u8_freeze = siphash(&str_bytes("u8"));
+    let inner = named("a", u8_freeze);
+    assert_eq!(Wrapper::<u8>::freeze_v1(), inner);
+    assert_eq!(Wrapper::<Wrapper<u8>>::freeze_v1(), named("a", inner));
+    assert_eq!(Wrapper::<Wrapper<u8>>::freeze_v1(), 596700380737705296);
+
+    // frozone 0.4.2 froze the inner `Wrapper<u8>` as a back-edge to the
+    // outer one, `Recursion { depth: 1 }`
+    assert_eq!(named("a", 2), 4345641587344347687);
+    assert_ne!(Wrapper::<Wrapper<u8>>::freeze_v1(), 4345641587344347687);
+}